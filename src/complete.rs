@@ -0,0 +1,135 @@
+/// Most bits a completion may have to guess, so that the search finishes in seconds.
+pub(crate) const MAX_FREE_BITS: u32 = 20;
+
+/// Bits of a decrypted character that the known game data fixes.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Pin {
+    mask: u8,
+    value: u8,
+}
+
+impl Pin {
+    pub(crate) fn bit_count(self) -> u32 {
+        self.mask.count_ones()
+    }
+
+    fn matches(self, decrypted: u8) -> bool {
+        decrypted & self.mask == self.value
+    }
+}
+
+/// Reads a partial password as character codes, with `None` for each unknown position.
+///
+/// Unknown positions are marked with `?` (or `？`), and the password is padded with
+/// unknown positions up to `length`.
+pub(crate) fn parse_pattern(
+    input: &str,
+    length: usize,
+    table: &[char; 64],
+) -> Result<Vec<Option<u8>>, String> {
+    let mut pattern = input
+        .chars()
+        .map(|c| match c {
+            '?' | '？' => Ok(None),
+            _ => table
+                .iter()
+                .position(|&moji| moji == c)
+                .map(|index| Some(index as u8))
+                .ok_or_else(|| format!("Unsupported input character: {}", c)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if pattern.len() > length {
+        return Err(format!("The password is longer than {} characters.", length));
+    }
+    pattern.resize(length, None);
+    Ok(pattern)
+}
+
+/// Pins the bits of the layout fields selected by `fixed` to their values.
+///
+/// `position` maps a bit of the packed data to the index of the character holding it
+/// and the shift of the bit within that character.
+pub(crate) fn pin_fields(
+    layout: &[(String, u32, u32)],
+    length: usize,
+    fixed: impl Fn(&str) -> bool,
+    position: impl Fn(u32) -> (usize, u32),
+) -> Vec<Pin> {
+    let mut pins = vec![Pin::default(); length];
+    let mut offset = 0;
+    for (label, bits, value) in layout {
+        if fixed(label) {
+            for bit in 0..*bits {
+                // Fields are written most significant bit first
+                let bit_value = (value >> (bits - 1 - bit) & 1) as u8;
+                let (index, shift) = position(offset + bit);
+                if let Some(pin) = pins.get_mut(index) {
+                    pin.mask |= 1 << shift;
+                    pin.value |= bit_value << shift;
+                }
+            }
+        }
+        offset += bits;
+    }
+    pins
+}
+
+/// Refuses a search with more than `MAX_FREE_BITS` bits to guess.
+///
+/// The bits are estimated as those of the unknown characters, less the pinned bits of
+/// the characters whose decryption depends on them.
+pub(crate) fn check_free_bits(pattern: &[Option<u8>], pins: &[Pin]) -> Result<(), String> {
+    let unknown = pattern.iter().filter(|code| code.is_none()).count() as u32;
+    let pinned: u32 = (0..pattern.len())
+        .filter(|&index| pattern[index].is_none() || index > 0 && pattern[index - 1].is_none())
+        .map(|index| pins[index].bit_count())
+        .sum();
+    let free = (unknown * 6).saturating_sub(pinned);
+    if free > MAX_FREE_BITS {
+        return Err(format!(
+            "Too many unknown bits to search ({}, at most {}); give the name, flags or more characters.",
+            free, MAX_FREE_BITS
+        ));
+    }
+    Ok(())
+}
+
+/// Calls `visit` with the character codes of every completion of the pattern whose
+/// decrypted characters match the pins.
+///
+/// `decrypt_last` gives the decrypted value of the last character of a partial password.
+pub(crate) fn search(
+    pattern: &[Option<u8>],
+    pins: &[Pin],
+    decrypt_last: fn(&[u8]) -> u8,
+    visit: &mut dyn FnMut(&[u8]),
+) {
+    let mut codes = Vec::with_capacity(pattern.len());
+    extend(pattern, pins, decrypt_last, &mut codes, visit);
+}
+
+fn extend(
+    pattern: &[Option<u8>],
+    pins: &[Pin],
+    decrypt_last: fn(&[u8]) -> u8,
+    codes: &mut Vec<u8>,
+    visit: &mut dyn FnMut(&[u8]),
+) {
+    let index = codes.len();
+    if index == pattern.len() {
+        visit(codes);
+        return;
+    }
+
+    let candidates = match pattern[index] {
+        Some(code) => code..=code,
+        None => 0..=63,
+    };
+    for code in candidates {
+        codes.push(code);
+        if pins[index].matches(decrypt_last(codes)) {
+            extend(pattern, pins, decrypt_last, codes, visit);
+        }
+        codes.pop();
+    }
+}
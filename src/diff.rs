@@ -0,0 +1,100 @@
+use prettytable::{row, Table};
+use std::fmt::Display;
+
+use crate::input;
+
+/// A field that differs between two game states.
+pub(crate) struct Change {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+    // What changed, such as a delta or the items gained and lost
+    pub summary: String,
+}
+
+/// Compares a field whose values are only shown, such as a name.
+pub(crate) fn value<T: PartialEq + Display>(field: &str, before: T, after: T) -> Option<Change> {
+    (before != after).then(|| Change {
+        field: field.to_string(),
+        before: before.to_string(),
+        after: after.to_string(),
+        summary: String::new(),
+    })
+}
+
+/// Compares a numeric field, summarising the delta.
+pub(crate) fn number(field: &str, before: i64, after: i64) -> Option<Change> {
+    (before != after).then(|| Change {
+        field: field.to_string(),
+        before: before.to_string(),
+        after: after.to_string(),
+        summary: format!("{:+}", after - before),
+    })
+}
+
+/// Compares a list of items, summarising the items gained (`+`) and lost (`-`).
+pub(crate) fn list(field: &str, before: &[&str], after: &[&str]) -> Option<Change> {
+    if before == after {
+        return None;
+    }
+
+    // Items are matched one for one, so that duplicates are counted
+    let mut lost = before.to_vec();
+    let mut gained = vec![];
+    for item in after {
+        match lost.iter().position(|other| other == item) {
+            Some(index) => {
+                lost.remove(index);
+            }
+            None => gained.push(*item),
+        }
+    }
+    let summary = gained
+        .iter()
+        .map(|item| format!("+{}", item))
+        .chain(lost.iter().map(|item| format!("-{}", item)))
+        .collect::<Vec<_>>();
+
+    Some(Change {
+        field: field.to_string(),
+        before: before.join("\n"),
+        after: after.join("\n"),
+        // If nothing was gained or lost, only the order of the items changed
+        summary: if summary.is_empty() { "reordered".to_string() } else { summary.join("\n") },
+    })
+}
+
+/// Compares a set of named flags, summarising the flags set (`○`) and cleared (`☓`).
+pub(crate) fn flags(
+    field: &str,
+    names: &[&str],
+    before: &[bool],
+    after: &[bool],
+) -> Option<Change> {
+    (before != after).then(|| Change {
+        field: field.to_string(),
+        before: input::format_flags(before),
+        after: input::format_flags(after),
+        summary: names
+            .iter()
+            .zip(before.iter().zip(after))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(name, (_, &after))| format!("{} {}", if after { "○" } else { "☓" }, name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    })
+}
+
+/// Formats the changes as a table, or a note if there are none.
+pub(crate) fn tabulate_changes(changes: &[Change]) -> String {
+    if changes.is_empty() {
+        return "No differences.".to_string();
+    }
+
+    let mut table = Table::new();
+    table.add_row(row!["Field", "Before", "After", "Change"]);
+    for change in changes {
+        table.add_row(row![change.field, change.before, change.after, change.summary]);
+    }
+    table.to_string()
+}
@@ -1,7 +1,27 @@
 use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter};
-use colored::Colorize;
 use prettytable::{row, Cell, Row, Table};
 
+use crate::input;
+use crate::item::CatalogueItem;
+use crate::keypad;
+use crate::rule;
+
+mod complete;
+mod diff;
+mod explain;
+mod items;
+mod ram;
+mod rules;
+mod score;
+mod stats;
+mod vanity;
+
+pub(crate) use complete::complete;
+pub(crate) use explain::explain;
+pub(crate) use items::{tabulate_catalogue, Armor, Item, Shield, Weapon};
+pub(crate) use score::score_candidates;
+pub(crate) use vanity::vanity;
+
 pub(crate) const JUMON_MOJI_TABLE: [char; 64] = [
     'あ', 'い', 'う', 'え', 'お', 'か', 'き', 'く', 'け', 'こ', 'さ', 'し', 'す', 'せ', 'そ', 'た',
     'ち', 'つ', 'て', 'と', 'な', 'に', 'ぬ', 'ね', 'の', 'は', 'ひ', 'ふ', 'へ', 'ほ', 'ま', 'み',
@@ -16,50 +36,6 @@ pub(crate) const NAME_MOJI_TABLE: [char; 64] = [
     'ら', 'り', 'る', 'れ', 'ろ', 'わ', 'を', 'ん', 'っ', 'ゃ', 'ゅ', 'ょ', '゛', '゜', 'ー', '　',
 ];
 
-pub(crate) const WEAPON_TABLE: [&str; 8] = [
-    "（なし）",
-    "たけざお",
-    "こんぼう",
-    "どうのつるぎ",
-    "てつのおの",
-    "はがねのつるぎ",
-    "ほのおのつるぎ",
-    "ロトのつるぎ",
-];
-
-pub(crate) const ARMOR_TABLE: [&str; 8] = [
-    "（なし）",
-    "ぬののふく",
-    "かわのふく",
-    "くさりかたびら",
-    "てつのよろい",
-    "はがねのよろい",
-    "まほうのよろい",
-    "ロトのよろい",
-];
-
-pub(crate) const SHIELD_TABLE: [&str; 4] =
-    ["（なし）", "かわのたて", "てつのたて", "みかがみのたて"];
-
-pub(crate) const ITEM_TABLE: [&str; 16] = [
-    "（なし）",
-    "たいまつ",
-    "せいすい",
-    "キメラのつばさ",
-    "りゅうのうろこ",
-    "ようせいのふえ",
-    "せんしのゆびわ",
-    "ロトのしるし",
-    "おうじょのあい",
-    "のろいのベルト",
-    "ぎんのたてごと",
-    "しのくびかざり",
-    "たいようのいし",
-    "あまぐものつえ",
-    "にじのしずく",
-    "（不正）",
-];
-
 pub(crate) const PROGRESS_FLAG_TABLE: [&str; 5] = [
     "りゅうのうろこ装備中",
     "せんしのゆびわ装備中",
@@ -68,7 +44,34 @@ pub(crate) const PROGRESS_FLAG_TABLE: [&str; 5] = [
     "しのくびかざり取得済み",
 ];
 
-#[derive(Clone, Copy, Debug, Default)]
+/// Largest encryption key; each key gives a different password for the same data.
+pub(crate) const MAX_ENCRYPTION_KEY: u8 = 0b111;
+
+/// Numeric fields that can be maximised, with the largest value each can hold.
+pub(crate) const OBJECTIVES: [(&str, u32); 4] = [
+    ("experience", u16::MAX as u32),
+    ("gold", u16::MAX as u32),
+    ("herbs", 0b1111),
+    ("keys", 0b1111),
+];
+
+/// The kana grid of the password screen, in blocks of five columns by consonant row.
+pub(crate) const PASSWORD_GRID: keypad::Grid = keypad::Grid {
+    rows: &[
+        "あいうえおはひふへほがぎぐげご",
+        "かきくけこまみむめもざじずぜぞ",
+        "さしすせそや　ゆ　よだぢづでど",
+        "たちつてとらりるれろばびぶべぼ",
+        "なにぬねのわ　　　　　　　　　",
+    ],
+    end: (4, 10),
+    wrap: true,
+};
+
+/// Sizes of the groups of characters in each row of the password as the game shows it.
+pub(crate) const PASSWORD_LAYOUT: [usize; 2] = [5, 5];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct GameData {
     // Player name: 4 characters (from `NAME_MOJI_TABLE`, 6 bits each)
     pub name: [char; 4],
@@ -77,17 +80,17 @@ pub(crate) struct GameData {
     // Gold: 16 bits
     pub gold: u16,
     // Weapon ID: 3 bits
-    pub weapon: u8,
+    pub weapon: Weapon,
     // Armor ID: 3 bits
-    pub armor: u8,
+    pub armor: Armor,
     // Shield ID: 2 bits
-    pub shield: u8,
+    pub shield: Shield,
     // やくそう count: 4 bits
     pub herbs: u8,
     // まほうのカギ count: 4 bits
     pub keys: u8,
     // Item IDs: 8 slots, 4 bits each
-    pub items: [u8; 8],
+    pub items: [Item; 8],
     // Progress flags, 5 total, 1 bit each
     pub progress_flags: [bool; 5],
     // Encryption key, 3 bits
@@ -96,14 +99,33 @@ pub(crate) struct GameData {
     pub checksum: u8,
 }
 
+impl Default for GameData {
+    fn default() -> Self {
+        Self {
+            name: ['　'; 4],
+            experience: 0,
+            gold: 0,
+            weapon: Weapon::Nothing,
+            armor: Armor::Nothing,
+            shield: Shield::Nothing,
+            herbs: 0,
+            keys: 0,
+            items: [Item::Nothing; 8],
+            progress_flags: [false; 5],
+            encryption_key: 0,
+            checksum: 0,
+        }
+    }
+}
+
 impl GameData {
     pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
         let mut data = Self::default();
 
         let mut reader = BitReader::endian(bytes, BigEndian);
 
-        data.items[1] = reader.read::<u8>(4).unwrap();
-        data.items[0] = reader.read::<u8>(4).unwrap();
+        data.items[1] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
+        data.items[0] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
 
         data.progress_flags[0] = reader.read::<u8>(1).unwrap() != 0;
         data.name[1] = NAME_MOJI_TABLE[reader.read::<u8>(6).unwrap() as usize];
@@ -111,24 +133,24 @@ impl GameData {
 
         data.experience |= reader.read::<u16>(8).unwrap() << 8;
 
-        data.items[5] = reader.read::<u8>(4).unwrap();
-        data.items[4] = reader.read::<u8>(4).unwrap();
+        data.items[5] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
+        data.items[4] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
 
         data.herbs = reader.read::<u8>(4).unwrap();
         data.keys = reader.read::<u8>(4).unwrap();
 
         data.gold |= reader.read::<u16>(8).unwrap() << 8;
 
-        data.weapon = reader.read::<u8>(3).unwrap();
-        data.armor = reader.read::<u8>(3).unwrap();
-        data.shield = reader.read::<u8>(2).unwrap();
+        data.weapon = Weapon::from_id(reader.read::<u8>(3).unwrap()).unwrap();
+        data.armor = Armor::from_id(reader.read::<u8>(3).unwrap()).unwrap();
+        data.shield = Shield::from_id(reader.read::<u8>(2).unwrap()).unwrap();
 
         data.encryption_key |= reader.read::<u8>(1).unwrap() << 2;
         data.progress_flags[2] = reader.read::<u8>(1).unwrap() != 0;
         data.name[3] = NAME_MOJI_TABLE[reader.read::<u8>(6).unwrap() as usize];
 
-        data.items[7] = reader.read::<u8>(4).unwrap();
-        data.items[6] = reader.read::<u8>(4).unwrap();
+        data.items[7] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
+        data.items[6] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
 
         data.name[0] = NAME_MOJI_TABLE[reader.read::<u8>(6).unwrap() as usize];
         data.progress_flags[3] = reader.read::<u8>(1).unwrap() != 0;
@@ -136,8 +158,8 @@ impl GameData {
 
         data.gold |= reader.read::<u16>(8).unwrap();
 
-        data.items[3] = reader.read::<u8>(4).unwrap();
-        data.items[2] = reader.read::<u8>(4).unwrap();
+        data.items[3] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
+        data.items[2] = Item::from_id(reader.read::<u8>(4).unwrap()).unwrap();
 
         data.encryption_key |= reader.read::<u8>(1).unwrap();
         data.progress_flags[4] = reader.read::<u8>(1).unwrap() != 0;
@@ -149,11 +171,176 @@ impl GameData {
 
         data
     }
+
+    /// Lists the fields in the order they are packed, as `(label, bit count, value)`.
+    pub(crate) fn layout(&self) -> Vec<(String, u32, u32)> {
+        let name = self.name.map(|c| NAME_MOJI_TABLE.iter().position(|&moji| moji == c).unwrap());
+        let item = |index: usize| (format!("items[{}]", index), 4, self.items[index].id() as u32);
+        let flag =
+            |index: usize| (format!("flags[{}]", index), 1, self.progress_flags[index] as u32);
+        let name_char = |index: usize| (format!("name[{}]", index), 6, name[index] as u32);
+        let key_bit =
+            |bit: u32| (format!("key (bit {})", bit), 1, self.encryption_key as u32 >> bit & 1);
+
+        vec![
+            item(1),
+            item(0),
+            flag(0),
+            name_char(1),
+            flag(1),
+            ("experience (high)".to_string(), 8, self.experience as u32 >> 8),
+            item(5),
+            item(4),
+            ("herbs".to_string(), 4, self.herbs as u32),
+            ("keys".to_string(), 4, self.keys as u32),
+            ("gold (high)".to_string(), 8, self.gold as u32 >> 8),
+            ("weapon".to_string(), 3, self.weapon.id() as u32),
+            ("armor".to_string(), 3, self.armor.id() as u32),
+            ("shield".to_string(), 2, self.shield.id() as u32),
+            key_bit(2),
+            flag(2),
+            name_char(3),
+            item(7),
+            item(6),
+            name_char(0),
+            flag(3),
+            key_bit(1),
+            ("gold (low)".to_string(), 8, self.gold as u32 & 0xFF),
+            item(3),
+            item(2),
+            key_bit(0),
+            flag(4),
+            name_char(2),
+            ("experience (low)".to_string(), 8, self.experience as u32 & 0xFF),
+            ("checksum".to_string(), 8, self.checksum as u32),
+        ]
+    }
+
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+        for (_, bits, value) in self.layout() {
+            writer.write(bits, value).unwrap();
+        }
+        writer.into_writer()
+    }
+
+    /// Describes how the passwords for two game states differ, or returns `None` if
+    /// they differ in anything other than the encryption key, checksum or unused bits.
+    pub(crate) fn state_difference(&self, other: &GameData) -> Option<&'static str> {
+        let strip = |data: &GameData| GameData { encryption_key: 0, checksum: 0, ..*data };
+        if strip(self) != strip(other) {
+            return None;
+        }
+        Some(match (self.encryption_key != other.encryption_key, self.checksum != other.checksum) {
+            (true, true) => "key and checksum",
+            (true, false) => "key only",
+            (false, true) => "checksum only",
+            (false, false) => "unused bits only",
+        })
+    }
+
+    /// Replaces the stored checksum with the one matching the rest of the data.
+    pub(crate) fn update_checksum(&mut self) {
+        let bytes = self.to_bytes();
+        self.checksum = checksum(&bytes[..bytes.len() - 1]);
+    }
+
+    /// Builds game data from `field=value` assignments, starting from the defaults.
+    pub(crate) fn from_assignments(assignments: &[(String, String)]) -> Result<Self, String> {
+        let mut data = Self::default();
+        for (field, value) in assignments.iter() {
+            data.set_field(field, value)?;
+        }
+        Ok(data)
+    }
+
+    /// Sets a single field from its textual representation, as used by `--set` and state files.
+    pub(crate) fn set_field(&mut self, field: &str, value: &str) -> Result<(), String> {
+        match field {
+            "name" => self.name = input::parse_game_name(value, &NAME_MOJI_TABLE)?,
+            "experience" => self.experience = input::parse_number(value, u16::MAX as u32)? as u16,
+            "gold" => self.gold = input::parse_number(value, u16::MAX as u32)? as u16,
+            "weapon" => self.weapon = Weapon::parse(value)?,
+            "armor" => self.armor = Armor::parse(value)?,
+            "shield" => self.shield = Shield::parse(value)?,
+            "herbs" => self.herbs = input::parse_number(value, 0b1111)? as u8,
+            "keys" => self.keys = input::parse_number(value, 0b1111)? as u8,
+            "items" => {
+                let items = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(Item::parse)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if items.len() > self.items.len() {
+                    return Err(format!(
+                        "Too many items (at most {}): {}",
+                        self.items.len(),
+                        value
+                    ));
+                }
+                self.items = [Item::Nothing; 8];
+                self.items[..items.len()].copy_from_slice(&items);
+            }
+            "flags" => self.progress_flags = input::parse_flag_array(value)?,
+            "key" => {
+                self.encryption_key = input::parse_number(value, MAX_ENCRYPTION_KEY as u32)? as u8
+            }
+            "checksum" => self.checksum = input::parse_number(value, u8::MAX as u32)? as u8,
+            _ => return Err(format!("Unknown DQ1 field: {}", field)),
+        }
+        Ok(())
+    }
+
+    /// Returns the names of the items held, up to the last occupied slot.
+    pub(crate) fn item_names(&self) -> Vec<&'static str> {
+        let item_count =
+            self.items.iter().rposition(|&item| item != Item::Nothing).map_or(0, |i| i + 1);
+        self.items[..item_count].iter().map(|item| item.name()).collect()
+    }
+
+    /// Lists every field as a `(field, value)` pair accepted by `set_field`.
+    pub(crate) fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.name.iter().collect()),
+            ("experience", self.experience.to_string()),
+            ("gold", self.gold.to_string()),
+            ("weapon", self.weapon.name().to_string()),
+            ("armor", self.armor.name().to_string()),
+            ("shield", self.shield.name().to_string()),
+            ("herbs", self.herbs.to_string()),
+            ("keys", self.keys.to_string()),
+            ("items", self.item_names().join(",")),
+            ("flags", input::format_flags(&self.progress_flags)),
+            ("key", self.encryption_key.to_string()),
+            ("checksum", self.checksum.to_string()),
+        ]
+    }
 }
 
-pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
-    // Convert input unicode characters to their corresponding 6-bit character codes
-    let jumon_chars: Vec<u8> = input
+/// Calculates the checksum (XMODEM-CRC) of the data bytes, excluding the checksum byte.
+fn checksum(bytes: &[u8]) -> u8 {
+    let mut crc = 0_u8;
+    let mut divisor = 0x8000_u16;
+    for byte in bytes.iter() {
+        for bit in 0..8 {
+            if divisor & 0x8000 != 0 {
+                divisor = (divisor << 1) ^ 0x1021;
+            } else {
+                divisor <<= 1;
+            }
+
+            if *byte & (1 << bit) != 0 {
+                crc ^= divisor as u8;
+            }
+        }
+    }
+    crc
+}
+
+/// Converts password characters to their 6-bit character codes.
+fn character_codes(input: &str) -> Result<Vec<u8>, String> {
+    input
         .chars()
         .map(|c| {
             JUMON_MOJI_TABLE
@@ -162,38 +349,33 @@ pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
                 .map(|index| index as u8)
                 .ok_or_else(|| format!("Unsupported input character: {}", c))
         })
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect()
+}
 
-    // Decrypt characters.
+/// Decrypts character codes into the 6-bit values they carry.
+fn decrypt(jumon_chars: &[u8]) -> Vec<u8> {
     let mut decrypted = vec![];
     let key = 0b100;
-    for (prev, cur) in std::iter::once(0).chain(jumon_chars.clone()).zip(jumon_chars) {
+    for (prev, &cur) in std::iter::once(0).chain(jumon_chars.iter().copied()).zip(jumon_chars) {
         decrypted.push(cur.wrapping_sub(prev).wrapping_sub(key) & 0b0011_1111);
     }
+    decrypted
+}
 
-    // Pack characters into bytes
+/// Packs decrypted characters into bytes, last character first.
+fn pack(decrypted: &[u8]) -> Vec<u8> {
     let mut writer = BitWriter::endian(Vec::new(), BigEndian);
     for character in decrypted.iter().rev() {
         writer.write(6, *character).unwrap();
     }
-    let input_bytes = writer.into_writer();
+    writer.into_writer()
+}
 
-    // Calculate the correct checksum (XMODEM-CRC)
-    let mut crc = 0_u8;
-    let mut divisor = 0x8000_u16;
-    for byte in input_bytes[..input_bytes.len() - 1].iter() {
-        for bit in 0..8 {
-            if divisor & 0x8000 != 0 {
-                divisor = (divisor << 1) ^ 0x1021;
-            } else {
-                divisor <<= 1;
-            }
+pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
+    let input_bytes = pack(&decrypt(&character_codes(input)?));
 
-            if *byte & (1 << bit) != 0 {
-                crc ^= divisor as u8;
-            }
-        }
-    }
+    // Calculate the correct checksum
+    let crc = checksum(&input_bytes[..input_bytes.len() - 1]);
 
     // Confirm that the CRC is correct
     if crc == *input_bytes.last().unwrap() {
@@ -203,12 +385,94 @@ pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
     }
 }
 
-pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) -> String {
+/// Decodes a password into game data without checking its length or checksum.
+pub(crate) fn decode_unchecked(input: &str) -> Result<GameData, String> {
+    let codes = character_codes(input)?;
+    if codes.len() != 20 {
+        return Err("DQ1 passwords are 20 characters long.".to_string());
+    }
+    Ok(GameData::from_bytes(&pack(&decrypt(&codes))))
+}
+
+/// Decrypts and packs a password into its 15 bytes without checking its checksum.
+pub(crate) fn packed_bytes(input: &str) -> Result<Vec<u8>, String> {
+    let codes = character_codes(input)?;
+    if codes.len() != 20 {
+        return Err("DQ1 passwords are 20 characters long.".to_string());
+    }
+    Ok(pack(&decrypt(&codes)))
+}
+
+/// Encodes the 15 packed bytes of a password, as given by `packed_bytes`.
+pub(crate) fn password_from_bytes(bytes: &[u8]) -> Result<String, String> {
+    if bytes.len() != 15 {
+        return Err(format!("DQ1 passwords pack into 15 bytes, not {}.", bytes.len()));
+    }
+    Ok(encode_jumon(bytes))
+}
+
+pub(crate) fn encode_jumon(bytes: &[u8]) -> String {
+    // Unpack bytes into characters, in the reverse order of `decode_jumon`
+    let mut reader = BitReader::endian(bytes, BigEndian);
+    let mut decrypted = vec![];
+    for _ in 0..bytes.len() * 8 / 6 {
+        decrypted.push(reader.read::<u8>(6).unwrap());
+    }
+    decrypted.reverse();
+
+    // Encrypt characters.
+    let mut output = String::with_capacity(decrypted.len());
+    let key = 0b100;
+    let mut prev = 0_u8;
+    for character in decrypted {
+        prev = character.wrapping_add(prev).wrapping_add(key) & 0b0011_1111;
+        output.push(JUMON_MOJI_TABLE[prev as usize]);
+    }
+    output
+}
+
+/// Re-encodes the data of a password with its checksum recomputed, keeping every data
+/// bit, as the equivalent password that differs from the input in the fewest characters.
+///
+/// Each character chains into the decryption of the next, so the characters after the
+/// ones carrying the checksum may change too.
+pub(crate) fn repair_checksum(input: &str) -> Result<String, String> {
+    let data = decode_unchecked(input)?;
+    let changes = |password: &String| {
+        let same = password.chars().zip(input.chars()).filter(|(a, b)| a == b).count();
+        password.chars().count().max(input.chars().count()) - same
+    };
+    Ok(equivalent_passwords(&data)
+        .into_iter()
+        .map(|(_, password)| password)
+        .min_by_key(changes)
+        .unwrap())
+}
+
+/// Encodes game data into a valid password, recomputing its checksum.
+pub(crate) fn encode_game_data(data: &GameData) -> String {
+    let mut data = *data;
+    data.update_checksum();
+    encode_jumon(&data.to_bytes())
+}
+
+/// Encodes the game data with every encryption key, giving all equivalent passwords.
+pub(crate) fn equivalent_passwords(data: &GameData) -> Vec<(u8, String)> {
+    (0..=MAX_ENCRYPTION_KEY)
+        .map(|key| (key, encode_game_data(&GameData { encryption_key: key, ..*data })))
+        .collect()
+}
+
+/// Tabulates candidates with the scores they were ranked by.
+pub(crate) fn tabulate_game_data(data: Vec<(String, GameData, i32)>, input: &str) -> String {
     // Create the table headers
     let mut table = Table::new();
     let header_row = row![
         "Player Name",
         "Experience",
+        "Level",
+        "Stats",
+        "Spells",
         "Gold",
         "Weapon",
         "Armor",
@@ -217,23 +481,16 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
         "Keys",
         "Items",
         "Progress Flags",
-        "Checksum"
+        "Checksum",
+        "Score",
+        "Notes"
     ];
     table.add_row(header_row.clone());
 
-    // Iterate over each `(String, GameData)` tuple and add its information to the table
-    for (label, game_data) in data {
+    // Iterate over each `(String, GameData, i32)` tuple and add its information to the table
+    for (label, game_data, score) in data {
         // Set the color of any substituted characters in the label to red
-        let mut formatted_label = String::with_capacity(input.len());
-        for (index, input_character) in input.chars().enumerate() {
-            let label_character = label.char_indices().nth(index).unwrap().1;
-            if input_character == label_character {
-                formatted_label.push(input_character);
-            } else {
-                formatted_label =
-                    format!("{}{}", formatted_label, label_character.to_string().red());
-            }
-        }
+        let formatted_label = input::highlight_substitutions(&label, input);
 
         // Add the label as a single row spanning the entire table width, to keep it compact
         table.add_row(Row::new(vec![Cell::new(&formatted_label).with_hspan(header_row.len())]));
@@ -242,14 +499,21 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
         let mut cells = Vec::new();
         cells.push(Cell::new(&game_data.name.iter().collect::<String>()));
         cells.push(Cell::new(&game_data.experience.to_string()));
+        let stats = game_data.stats();
+        cells.push(Cell::new(&stats.level.to_string()));
+        cells.push(Cell::new(&format!(
+            "HP: {}\nMP: {}\nStrength: {}\nAgility: {}\nAttack: {}\nDefence: {}",
+            stats.max_hp, stats.max_mp, stats.strength, stats.agility, stats.attack, stats.defence
+        )));
+        cells.push(Cell::new(&stats.spells.join("\n")));
         cells.push(Cell::new(&game_data.gold.to_string()));
-        cells.push(Cell::new(WEAPON_TABLE[game_data.weapon as usize]));
-        cells.push(Cell::new(ARMOR_TABLE[game_data.armor as usize]));
-        cells.push(Cell::new(SHIELD_TABLE[game_data.shield as usize]));
+        cells.push(Cell::new(game_data.weapon.name()));
+        cells.push(Cell::new(game_data.armor.name()));
+        cells.push(Cell::new(game_data.shield.name()));
         cells.push(Cell::new(&game_data.herbs.to_string()));
         cells.push(Cell::new(&game_data.keys.to_string()));
         cells.push(Cell::new(
-            &game_data.items.iter().map(|&x| ITEM_TABLE[x as usize]).collect::<Vec<_>>().join("\n"),
+            &game_data.items.iter().map(|x| x.name()).collect::<Vec<_>>().join("\n"),
         ));
         cells.push(Cell::new(
             &game_data
@@ -265,6 +529,8 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
         cells.push(Cell::new(
             format!("{} (Key: {})", game_data.checksum, game_data.encryption_key).as_str(),
         ));
+        cells.push(Cell::new(&score.to_string()));
+        cells.push(Cell::new(&rule::format_violations(&game_data.validate())));
 
         table.add_row(Row::new(cells));
     }
@@ -272,3 +538,74 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
     // Return the table as a `String`
     table.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_data(assignments: &[(&str, &str)]) -> GameData {
+        let assignments =
+            assignments.iter().map(|&(f, v)| (f.to_string(), v.to_string())).collect::<Vec<_>>();
+        GameData::from_assignments(&assignments).unwrap()
+    }
+
+    #[test]
+    fn repairs_checksum_keeping_the_data() {
+        let mut data = game_data(&[("name", "ゆうてい"), ("gold", "500"), ("key", "6")]);
+        data.update_checksum();
+        let valid = encode_jumon(&data.to_bytes());
+        assert_eq!(repair_checksum(&valid).unwrap(), valid);
+
+        let changes = |a: &str, b: &str| a.chars().zip(b.chars()).filter(|(a, b)| a != b).count();
+        for checksum in [0, 1, 0x80, data.checksum ^ 0xFF] {
+            if checksum == data.checksum {
+                continue;
+            }
+            let broken = encode_jumon(&GameData { checksum, ..data }.to_bytes());
+            assert!(decode_jumon(&broken).is_err());
+            let repaired = repair_checksum(&broken).unwrap();
+            let decoded = GameData::from_bytes(&decode_jumon(&repaired).unwrap());
+            assert!(decoded.state_difference(&data).is_some());
+            // The original password is one of the candidates, so no more characters change
+            assert!(changes(&repaired, &broken) <= changes(&valid, &broken));
+        }
+    }
+
+    #[test]
+    fn repair_keeps_the_data_of_a_mistyped_character() {
+        let valid = encode_game_data(&game_data(&[("experience", "1000"), ("key", "2")]));
+        for position in [0, 1, 5, 19] {
+            let mut broken = valid.chars().collect::<Vec<_>>();
+            broken[position] = if broken[position] == 'あ' { 'い' } else { 'あ' };
+            let broken = broken.into_iter().collect::<String>();
+            let repaired = repair_checksum(&broken).unwrap();
+            let decoded = GameData::from_bytes(&decode_jumon(&repaired).unwrap());
+            let written = decode_unchecked(&broken).unwrap();
+            assert!(decoded.state_difference(&written).is_some());
+        }
+    }
+    #[test]
+    fn every_equivalent_password_decodes_to_the_same_state() {
+        let data = game_data(&[
+            ("name", "ゆうてい"),
+            ("experience", "65535"),
+            ("gold", "1234"),
+            ("weapon", "7"),
+            ("armor", "7"),
+            ("shield", "3"),
+            ("herbs", "6"),
+            ("keys", "15"),
+            ("items", "1,2,3,4,5,6,7,14"),
+            ("flags", "10101"),
+        ]);
+        let passwords = equivalent_passwords(&data);
+        assert_eq!(passwords.len(), MAX_ENCRYPTION_KEY as usize + 1);
+        for (key, password) in passwords {
+            assert_eq!(password.chars().count(), 20);
+            let decoded = GameData::from_bytes(&decode_jumon(&password).unwrap());
+            assert_eq!(decoded.encryption_key, key);
+            assert!(decoded.state_difference(&data).is_some(), "key {}", key);
+            assert_eq!(password_from_bytes(&packed_bytes(&password).unwrap()).unwrap(), password);
+        }
+    }
+}
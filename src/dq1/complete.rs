@@ -0,0 +1,110 @@
+use super::{
+    checksum, decrypt, pack, GameData, Item, JUMON_MOJI_TABLE, NAME_MOJI_TABLE, PROGRESS_FLAG_TABLE,
+};
+use crate::complete::{check_free_bits, parse_pattern, pin_fields, search};
+use crate::item::CatalogueItem;
+use crate::rule;
+
+/// Decrypts the last character of a partial password.
+pub(super) fn decrypt_last(codes: &[u8]) -> u8 {
+    let prev = if codes.len() > 1 { codes[codes.len() - 2] } else { 0 };
+    codes[codes.len() - 1].wrapping_sub(prev).wrapping_sub(0b100) & 0b0011_1111
+}
+
+/// Lists the valid passwords that complete a partial password, keeping the name and
+/// progress flags if given and holding every item in `items`.
+pub(crate) fn complete(
+    input: &str,
+    name: Option<[char; 4]>,
+    progress_flags: Option<&[bool]>,
+    items: &[String],
+) -> Result<Vec<(String, GameData)>, String> {
+    let pattern = parse_pattern(input, 20, &JUMON_MOJI_TABLE)?;
+    let items = items.iter().map(|item| Item::parse(item)).collect::<Result<Vec<_>, _>>()?;
+
+    // Lay out the known fields, so that the search only tries characters that keep them
+    let mut known = GameData::default();
+    if let Some(name) = name {
+        if let Some(c) = name.iter().find(|c| !NAME_MOJI_TABLE.contains(c)) {
+            return Err(format!("Unsupported name character: {}", c));
+        }
+        known.name = name;
+    }
+    if let Some(flags) = progress_flags {
+        known.progress_flags = flags
+            .try_into()
+            .map_err(|_| format!("DQ1 has {} progress flags.", PROGRESS_FLAG_TABLE.len()))?;
+    }
+    let fixed = |label: &str| {
+        name.is_some() && label.starts_with("name[")
+            || progress_flags.is_some() && label.starts_with("flags[")
+    };
+    // Characters are packed last first
+    let pins = pin_fields(&known.layout(), pattern.len(), fixed, |bit| {
+        (pattern.len() - 1 - bit as usize / 6, 5 - bit % 6)
+    });
+    check_free_bits(&pattern, &pins)?;
+
+    let mut completions = vec![];
+    search(&pattern, &pins, decrypt_last, &mut |codes| {
+        let bytes = pack(&decrypt(codes));
+        if checksum(&bytes[..bytes.len() - 1]) != bytes[bytes.len() - 1] {
+            return;
+        }
+        let data = GameData::from_bytes(&bytes);
+        if rule::has_errors(&data.validate()) || !items.iter().all(|item| data.items.contains(item))
+        {
+            return;
+        }
+        let password = codes.iter().map(|&code| JUMON_MOJI_TABLE[code as usize]).collect();
+        completions.push((password, data));
+    });
+    Ok(completions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dq1::encode_game_data;
+
+    fn password() -> (String, GameData) {
+        let assignments =
+            [("name", "ゆうてい"), ("gold", "500"), ("items", "1,3"), ("flags", "00010")]
+                .map(|(field, value)| (field.to_string(), value.to_string()));
+        let data = GameData::from_assignments(&assignments).unwrap();
+        (encode_game_data(&data), data)
+    }
+
+    fn blank(password: &str, positions: &[usize]) -> String {
+        let chars = password.chars().enumerate();
+        chars.map(|(index, c)| if positions.contains(&index) { '?' } else { c }).collect()
+    }
+
+    #[test]
+    fn finds_the_password_among_completions() {
+        let (password, _) = password();
+        let completions = complete(&blank(&password, &[3, 19]), None, None, &[]).unwrap();
+        assert!(completions.iter().any(|(completion, _)| *completion == password));
+        assert!(completions.iter().all(|(completion, _)| completion.chars().count() == 20));
+
+        // Giving a held item rules out the completions without it
+        let items = ["たいまつ".to_string()];
+        let held = complete(&blank(&password, &[3, 19]), None, None, &items).unwrap();
+        assert!(held.iter().all(|(_, data)| data.items.contains(&Item::Torch)));
+        assert!(held.iter().any(|(completion, _)| *completion == password));
+        assert!(held.len() < completions.len());
+    }
+
+    #[test]
+    fn pins_the_name_and_flags_to_search_more_unknown_characters() {
+        let (password, data) = password();
+        // Characters 17 and 18 hold the flags and name character packed first
+        let pattern = blank(&password, &[15, 16, 17, 18]);
+        assert!(complete(&pattern, None, None, &[]).is_err());
+        let completions =
+            complete(&pattern, Some(data.name), Some(&data.progress_flags), &[]).unwrap();
+        assert!(completions.iter().any(|(completion, _)| *completion == password));
+        assert!(completions.iter().all(|(_, completed)| completed.name == data.name
+            && completed.progress_flags == data.progress_flags));
+    }
+}
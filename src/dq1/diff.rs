@@ -0,0 +1,40 @@
+use super::{stats, GameData, PROGRESS_FLAG_TABLE};
+use crate::diff::{self, Change};
+use crate::item::CatalogueItem;
+
+impl GameData {
+    /// Lists the fields that differ from `other`, which is taken to be the later state.
+    pub(crate) fn diff(&self, other: &GameData) -> Vec<Change> {
+        let name = |data: &GameData| data.name.iter().collect::<String>();
+        let experience = diff::number(
+            "experience",
+            self.experience.into(),
+            other.experience.into(),
+        )
+        .map(|mut change| {
+            let (before, after) = (stats::level(self.experience), stats::level(other.experience));
+            if before != after {
+                change.summary = format!("{} (level {} → {})", change.summary, before, after);
+            }
+            change
+        });
+
+        [
+            diff::value("name", name(self), name(other)),
+            experience,
+            diff::number("gold", self.gold.into(), other.gold.into()),
+            diff::value("weapon", self.weapon.name(), other.weapon.name()),
+            diff::value("armor", self.armor.name(), other.armor.name()),
+            diff::value("shield", self.shield.name(), other.shield.name()),
+            diff::number("herbs", self.herbs.into(), other.herbs.into()),
+            diff::number("keys", self.keys.into(), other.keys.into()),
+            diff::list("items", &self.item_names(), &other.item_names()),
+            diff::flags("flags", &PROGRESS_FLAG_TABLE, &self.progress_flags, &other.progress_flags),
+            diff::number("key", self.encryption_key.into(), other.encryption_key.into()),
+            diff::number("checksum", self.checksum.into(), other.checksum.into()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
@@ -0,0 +1,28 @@
+use super::GameData;
+use crate::explain;
+
+/// Explains how a password decodes, bit by bit, whether or not its checksum is valid.
+pub(crate) fn explain(input: &str) -> Result<String, String> {
+    let codes = super::character_codes(input)?;
+    if codes.len() != 20 {
+        return Err("DQ1 passwords are 20 characters long.".to_string());
+    }
+    let decrypted = super::decrypt(&codes);
+    let bytes = super::pack(&decrypted);
+    let data = GameData::from_bytes(&bytes);
+
+    // Characters are packed last first
+    let offsets = (0..codes.len()).map(|index| (codes.len() - 1 - index) * 6).collect::<Vec<_>>();
+    let (data_bytes, stored) = bytes.split_at(bytes.len() - 1);
+
+    Ok([
+        format!(
+            "Characters:\n{}",
+            explain::tabulate_characters(input, &codes, &decrypted, &offsets)
+        ),
+        format!("Bytes:\n{}", explain::tabulate_bytes(&bytes)),
+        format!("Fields:\n{}", explain::tabulate_layout(&bytes, &data.layout())),
+        explain::format_checksum(stored[0].into(), super::checksum(data_bytes).into()),
+    ]
+    .join("\n"))
+}
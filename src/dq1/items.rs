@@ -0,0 +1,265 @@
+use prettytable::{row, Table};
+
+use crate::item::{self, CatalogueItem, Category};
+
+/// Static metadata of an item in the catalogue.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ItemInfo {
+    pub name: &'static str,
+    pub category: Category,
+    pub attack: u8,
+    pub defence: u8,
+    // Shop price, if the item is sold anywhere
+    pub price: Option<u16>,
+    // Value when sold to a shop, if the item can be sold
+    pub sell_value: Option<u16>,
+    // Whether the item can only be obtained once
+    pub unique: bool,
+}
+
+/// Builds an `ItemInfo`, in field order, to keep the catalogues compact.
+const fn info(
+    name: &'static str,
+    category: Category,
+    attack: u8,
+    defence: u8,
+    price: Option<u16>,
+    sell_value: Option<u16>,
+    unique: bool,
+) -> ItemInfo {
+    ItemInfo { name, category, attack, defence, price, sell_value, unique }
+}
+
+const NOTHING: ItemInfo = info("（なし）", Category::Nothing, 0, 0, None, None, false);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Weapon {
+    #[default]
+    Nothing,
+    BambooPole,
+    Club,
+    CopperSword,
+    HandAxe,
+    BroadSword,
+    FlameSword,
+    ErdricksSword,
+}
+
+const WEAPON_CATALOGUE: [ItemInfo; 8] = [
+    NOTHING,
+    info("たけざお", Category::Weapon, 2, 0, Some(10), Some(5), false),
+    info("こんぼう", Category::Weapon, 4, 0, Some(60), Some(30), false),
+    info("どうのつるぎ", Category::Weapon, 10, 0, Some(180), Some(90), false),
+    info("てつのおの", Category::Weapon, 15, 0, Some(560), Some(280), false),
+    info("はがねのつるぎ", Category::Weapon, 20, 0, Some(1500), Some(750), false),
+    info("ほのおのつるぎ", Category::Weapon, 28, 0, Some(9800), Some(4900), false),
+    info("ロトのつるぎ", Category::Weapon, 40, 0, None, None, true),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Armor {
+    #[default]
+    Nothing,
+    Clothes,
+    Leather,
+    ChainMail,
+    HalfPlate,
+    FullPlate,
+    Magic,
+    Erdricks,
+}
+
+const ARMOR_CATALOGUE: [ItemInfo; 8] = [
+    NOTHING,
+    info("ぬののふく", Category::Armor, 0, 2, Some(20), Some(10), false),
+    info("かわのふく", Category::Armor, 0, 4, Some(70), Some(35), false),
+    info("くさりかたびら", Category::Armor, 0, 10, Some(300), Some(150), false),
+    info("てつのよろい", Category::Armor, 0, 16, Some(1000), Some(500), false),
+    info("はがねのよろい", Category::Armor, 0, 24, Some(3000), Some(1500), false),
+    info("まほうのよろい", Category::Armor, 0, 24, Some(7700), Some(3850), false),
+    info("ロトのよろい", Category::Armor, 0, 28, None, None, true),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Shield {
+    #[default]
+    Nothing,
+    Small,
+    Large,
+    Silver,
+}
+
+const SHIELD_CATALOGUE: [ItemInfo; 4] = [
+    NOTHING,
+    info("かわのたて", Category::Shield, 0, 4, Some(90), Some(45), false),
+    info("てつのたて", Category::Shield, 0, 10, Some(800), Some(400), false),
+    info("みかがみのたて", Category::Shield, 0, 20, Some(14800), Some(7400), false),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Item {
+    #[default]
+    Nothing,
+    Torch,
+    FairyWater,
+    Wings,
+    DragonsScale,
+    FairyFlute,
+    FightersRing,
+    ErdricksToken,
+    GwaelinsLove,
+    CursedBelt,
+    SilverHarp,
+    DeathNecklace,
+    StonesOfSunlight,
+    StaffOfRain,
+    RainbowDrop,
+    Invalid,
+}
+
+const ITEM_CATALOGUE: [ItemInfo; 16] = [
+    NOTHING,
+    info("たいまつ", Category::Consumable, 0, 0, Some(8), Some(4), false),
+    info("せいすい", Category::Consumable, 0, 0, Some(38), Some(19), false),
+    info("キメラのつばさ", Category::Consumable, 0, 0, Some(70), Some(35), false),
+    info("りゅうのうろこ", Category::Consumable, 0, 2, Some(20), Some(10), false),
+    info("ようせいのふえ", Category::KeyItem, 0, 0, None, None, true),
+    info("せんしのゆびわ", Category::KeyItem, 2, 0, None, None, true),
+    info("ロトのしるし", Category::KeyItem, 0, 0, None, None, true),
+    info("おうじょのあい", Category::KeyItem, 0, 0, None, None, true),
+    info("のろいのベルト", Category::KeyItem, 0, 0, None, Some(180), false),
+    info("ぎんのたてごと", Category::KeyItem, 0, 0, None, None, true),
+    info("しのくびかざり", Category::KeyItem, 0, 0, None, Some(1200), true),
+    info("たいようのいし", Category::KeyItem, 0, 0, None, None, true),
+    info("あまぐものつえ", Category::KeyItem, 0, 0, None, None, true),
+    info("にじのしずく", Category::KeyItem, 0, 0, None, None, true),
+    info("（不正）", Category::Invalid, 0, 0, None, None, false),
+];
+
+impl CatalogueItem for Weapon {
+    const ALL: &'static [Self] = &[
+        Weapon::Nothing,
+        Weapon::BambooPole,
+        Weapon::Club,
+        Weapon::CopperSword,
+        Weapon::HandAxe,
+        Weapon::BroadSword,
+        Weapon::FlameSword,
+        Weapon::ErdricksSword,
+    ];
+
+    fn name(self) -> &'static str {
+        self.info().name
+    }
+}
+
+impl CatalogueItem for Armor {
+    const ALL: &'static [Self] = &[
+        Armor::Nothing,
+        Armor::Clothes,
+        Armor::Leather,
+        Armor::ChainMail,
+        Armor::HalfPlate,
+        Armor::FullPlate,
+        Armor::Magic,
+        Armor::Erdricks,
+    ];
+
+    fn name(self) -> &'static str {
+        self.info().name
+    }
+}
+
+impl CatalogueItem for Shield {
+    const ALL: &'static [Self] = &[Shield::Nothing, Shield::Small, Shield::Large, Shield::Silver];
+
+    fn name(self) -> &'static str {
+        self.info().name
+    }
+}
+
+impl CatalogueItem for Item {
+    const ALL: &'static [Self] = &[
+        Item::Nothing,
+        Item::Torch,
+        Item::FairyWater,
+        Item::Wings,
+        Item::DragonsScale,
+        Item::FairyFlute,
+        Item::FightersRing,
+        Item::ErdricksToken,
+        Item::GwaelinsLove,
+        Item::CursedBelt,
+        Item::SilverHarp,
+        Item::DeathNecklace,
+        Item::StonesOfSunlight,
+        Item::StaffOfRain,
+        Item::RainbowDrop,
+        Item::Invalid,
+    ];
+
+    fn name(self) -> &'static str {
+        self.info().name
+    }
+}
+
+impl Weapon {
+    pub(crate) fn info(self) -> &'static ItemInfo {
+        &WEAPON_CATALOGUE[self as usize]
+    }
+}
+
+impl Armor {
+    pub(crate) fn info(self) -> &'static ItemInfo {
+        &ARMOR_CATALOGUE[self as usize]
+    }
+}
+
+impl Shield {
+    pub(crate) fn info(self) -> &'static ItemInfo {
+        &SHIELD_CATALOGUE[self as usize]
+    }
+}
+
+impl Item {
+    pub(crate) fn info(self) -> &'static ItemInfo {
+        &ITEM_CATALOGUE[self as usize]
+    }
+}
+
+/// Formats every weapon, armor, shield and item in the catalogue as a table.
+pub(crate) fn tabulate_catalogue() -> String {
+    let mut table = Table::new();
+    table.add_row(row![
+        "ID",
+        "Name",
+        "Category",
+        "Attack",
+        "Defence",
+        "Price",
+        "Sell Value",
+        "Unique"
+    ]);
+
+    let weapons = Weapon::ALL.iter().map(|item| (item.id(), item.info()));
+    let armor = Armor::ALL.iter().map(|item| (item.id(), item.info()));
+    let shields = Shield::ALL.iter().map(|item| (item.id(), item.info()));
+    let items = Item::ALL.iter().map(|item| (item.id(), item.info()));
+    for (id, info) in weapons.chain(armor).chain(shields).chain(items) {
+        if info.category == Category::Nothing {
+            continue;
+        }
+        table.add_row(row![
+            id,
+            info.name,
+            info.category.name(),
+            info.attack,
+            info.defence,
+            item::format_price(info.price),
+            item::format_price(info.sell_value),
+            if info.unique { "○" } else { "" }
+        ]);
+    }
+
+    table.to_string()
+}
@@ -0,0 +1,89 @@
+use super::{Armor, GameData, Item, Shield, Weapon, NAME_MOJI_TABLE};
+use crate::item::CatalogueItem;
+use crate::savestate::Ram;
+
+/// Hero name, as 4 codes of `NAME_MOJI_TABLE`.
+const NAME: usize = 0x00B5;
+/// Experience and gold, low byte first.
+const EXPERIENCE: usize = 0x00BA;
+const GOLD: usize = 0x00BC;
+/// Weapon, armor and shield IDs packed into a byte as in the password (3, 3 and 2 bits).
+const EQUIPMENT: usize = 0x00BE;
+const KEYS: usize = 0x00BF;
+const HERBS: usize = 0x00C0;
+/// Item IDs, two per byte with the first in the low 4 bits.
+const ITEMS: usize = 0x00C1;
+
+/// Address and bit of each progress flag, in the order of `PROGRESS_FLAG_TABLE`.
+const PROGRESS_FLAG_BITS: [(usize, u8); 5] =
+    [(0x00DF, 0x10), (0x00DF, 0x20), (0x00E4, 0x04), (0x00E4, 0x02), (0x00CF, 0x80)];
+
+impl GameData {
+    /// Reads the fields a password carries from the work RAM, with encryption key 0.
+    pub(crate) fn from_ram(ram: &Ram) -> Result<Self, String> {
+        let mut data = Self::default();
+        for (index, &code) in ram[NAME..NAME + 4].iter().enumerate() {
+            data.name[index] = *NAME_MOJI_TABLE.get(code as usize).ok_or_else(|| {
+                format!("Invalid name character code {} at ${:04X}", code, NAME + index)
+            })?;
+        }
+        data.experience = u16::from_le_bytes([ram[EXPERIENCE], ram[EXPERIENCE + 1]]);
+        data.gold = u16::from_le_bytes([ram[GOLD], ram[GOLD + 1]]);
+
+        let equipment = ram[EQUIPMENT];
+        data.weapon = Weapon::from_id(equipment >> 5).unwrap();
+        data.armor = Armor::from_id(equipment >> 2 & 0b111).unwrap();
+        data.shield = Shield::from_id(equipment & 0b11).unwrap();
+
+        // Counts beyond 4 bits cannot be written in a password
+        for (field, address) in [("keys", KEYS), ("herbs", HERBS)] {
+            if ram[address] > 0b1111 {
+                return Err(format!("Too many {} ({}) at ${:04X}", field, ram[address], address));
+            }
+        }
+        data.keys = ram[KEYS];
+        data.herbs = ram[HERBS];
+
+        for (index, &pair) in ram[ITEMS..ITEMS + 4].iter().enumerate() {
+            data.items[index * 2] = Item::from_id(pair & 0b1111).unwrap();
+            data.items[index * 2 + 1] = Item::from_id(pair >> 4).unwrap();
+        }
+        for (flag, &(address, bit)) in data.progress_flags.iter_mut().zip(&PROGRESS_FLAG_BITS) {
+            *flag = ram[address] & bit != 0;
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savestate;
+
+    #[test]
+    fn reads_fixture_dump() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dq1.ram");
+        let data = GameData::from_ram(&savestate::read_ram(path).unwrap()).unwrap();
+        let expected = [
+            ("name", "ゆうてい"),
+            ("experience", "1000"),
+            ("gold", "500"),
+            ("weapon", "3"),
+            ("armor", "2"),
+            ("shield", "1"),
+            ("herbs", "3"),
+            ("keys", "2"),
+            ("items", "1,2,3"),
+            ("flags", "10010"),
+        ]
+        .map(|(field, value)| (field.to_string(), value.to_string()));
+        assert_eq!(data, GameData::from_assignments(&expected).unwrap());
+    }
+
+    #[test]
+    fn rejects_counts_beyond_four_bits() {
+        let mut ram = [0; savestate::RAM_SIZE];
+        ram[HERBS] = 16;
+        assert!(GameData::from_ram(&ram).is_err());
+    }
+}
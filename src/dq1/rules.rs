@@ -0,0 +1,127 @@
+use super::{GameData, Item};
+use crate::item::{CatalogueItem, Category};
+use crate::rule::{self, Rule, Severity, Violation};
+
+/// Maximum number of やくそう or まほうのカギ that can be carried.
+pub(crate) const MAX_HERBS_OR_KEYS: u8 = 6;
+
+pub(crate) const RULES: [Rule<GameData>; 9] = [
+    Rule { name: "invalid-item", severity: Severity::Error, check: invalid_item },
+    Rule { name: "too-many-herbs", severity: Severity::Error, check: too_many_herbs },
+    Rule { name: "too-many-keys", severity: Severity::Error, check: too_many_keys },
+    Rule { name: "duplicate-unique-item", severity: Severity::Error, check: duplicate_unique_item },
+    Rule { name: "ring-without-ring", severity: Severity::Error, check: ring_without_ring },
+    Rule { name: "necklace-without-flag", severity: Severity::Error, check: necklace_without_flag },
+    Rule { name: "drop-with-ingredients", severity: Severity::Error, check: drop_with_ingredients },
+    Rule { name: "scale-without-scale", severity: Severity::Warning, check: scale_without_scale },
+    Rule { name: "item-gap", severity: Severity::Warning, check: item_gap },
+];
+
+impl GameData {
+    /// Checks the data against the DQ1 rule set.
+    pub(crate) fn validate(&self) -> Vec<Violation> {
+        rule::validate(&RULES, self)
+    }
+}
+
+fn invalid_item(data: &GameData) -> Option<String> {
+    data.items
+        .iter()
+        .any(|item| item.info().category == Category::Invalid)
+        .then(|| format!("Invalid item {}", Item::Invalid.name()))
+}
+
+fn too_many_herbs(data: &GameData) -> Option<String> {
+    (data.herbs > MAX_HERBS_OR_KEYS)
+        .then(|| format!("{} やくそう (at most {})", data.herbs, MAX_HERBS_OR_KEYS))
+}
+
+fn too_many_keys(data: &GameData) -> Option<String> {
+    (data.keys > MAX_HERBS_OR_KEYS)
+        .then(|| format!("{} まほうのカギ (at most {})", data.keys, MAX_HERBS_OR_KEYS))
+}
+
+fn duplicate_unique_item(data: &GameData) -> Option<String> {
+    let duplicates = Item::ALL
+        .iter()
+        .filter(|item| item.info().unique)
+        .filter(|&&item| data.items.iter().filter(|&&held| held == item).count() > 1)
+        .map(|item| item.name())
+        .collect::<Vec<_>>();
+    (!duplicates.is_empty()).then(|| format!("Duplicate unique item(s): {}", duplicates.join(", ")))
+}
+
+fn ring_without_ring(data: &GameData) -> Option<String> {
+    (data.progress_flags[1] && !data.items.contains(&Item::FightersRing))
+        .then(|| format!("{} equipped without holding one", Item::FightersRing.name()))
+}
+
+fn necklace_without_flag(data: &GameData) -> Option<String> {
+    (!data.progress_flags[4] && data.items.contains(&Item::DeathNecklace))
+        .then(|| format!("{} held without having obtained it", Item::DeathNecklace.name()))
+}
+
+fn drop_with_ingredients(data: &GameData) -> Option<String> {
+    // にじのしずく is made from たいようのいし and あまぐものつえ, which are used up.
+    let ingredients = [Item::StonesOfSunlight, Item::StaffOfRain]
+        .iter()
+        .filter(|ingredient| data.items.contains(ingredient))
+        .map(|ingredient| ingredient.name())
+        .collect::<Vec<_>>();
+    (data.items.contains(&Item::RainbowDrop) && !ingredients.is_empty())
+        .then(|| format!("{} held along with {}", Item::RainbowDrop.name(), ingredients.join(", ")))
+}
+
+fn scale_without_scale(data: &GameData) -> Option<String> {
+    (data.progress_flags[0] && !data.items.contains(&Item::DragonsScale))
+        .then(|| format!("{} equipped without holding one", Item::DragonsScale.name()))
+}
+
+fn item_gap(data: &GameData) -> Option<String> {
+    // The game fills item slots in order, so an empty slot is never followed by an item.
+    let first_empty = data.items.iter().position(|&item| item == Item::Nothing)?;
+    data.items[first_empty..]
+        .iter()
+        .any(|&item| item != Item::Nothing)
+        .then(|| "Item held after an empty slot".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violations(assignments: &[(&str, &str)]) -> Vec<(&'static str, Severity)> {
+        let assignments =
+            assignments.iter().map(|&(f, v)| (f.to_string(), v.to_string())).collect::<Vec<_>>();
+        let data = GameData::from_assignments(&assignments).unwrap();
+        data.validate().iter().map(|violation| (violation.rule, violation.severity)).collect()
+    }
+
+    #[test]
+    fn accepts_reachable_state() {
+        assert!(violations(&[("herbs", "6"), ("keys", "6"), ("items", "2,4")]).is_empty());
+    }
+
+    #[test]
+    fn rates_impossible_states_as_errors_and_unusual_ones_as_warnings() {
+        let cases = [
+            (("herbs", "7"), ("too-many-herbs", Severity::Error)),
+            (("keys", "15"), ("too-many-keys", Severity::Error)),
+            (("items", "13,13"), ("duplicate-unique-item", Severity::Error)),
+            (("flags", "01000"), ("ring-without-ring", Severity::Error)),
+            (("flags", "10000"), ("scale-without-scale", Severity::Warning)),
+            (("items", "0,2"), ("item-gap", Severity::Warning)),
+        ];
+        for (assignment, expected) in cases {
+            assert_eq!(violations(&[assignment]), [expected], "{:?}", assignment);
+        }
+    }
+
+    #[test]
+    fn only_errors_rule_a_state_out() {
+        let data = GameData::from_assignments(&[("flags".to_string(), "10000".to_string())]);
+        assert!(!rule::has_errors(&data.unwrap().validate()));
+        let data = GameData::from_assignments(&[("herbs".to_string(), "7".to_string())]);
+        assert!(rule::has_errors(&data.unwrap().validate()));
+    }
+}
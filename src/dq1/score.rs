@@ -0,0 +1,84 @@
+use super::{stats, GameData, Item};
+use crate::score::{self, Progress, MAX_SCORE};
+
+/// Gold given by the king at the start of the game.
+const STARTING_GOLD: u32 = 120;
+
+/// Level below which the party is unlikely to have found ロトの装備.
+const UNIQUE_EQUIPMENT_LEVEL: u8 = 12;
+
+/// Level below which the party is unlikely to have defeated the dragon or the golem.
+const BOSS_LEVEL: u8 = 10;
+
+/// Scores candidates against each other's names, pairing each with its score.
+pub(crate) fn score_candidates(
+    candidates: Vec<(String, GameData)>,
+) -> Vec<(String, GameData, i32)> {
+    let names = candidates.iter().map(|(_, data)| data.name).collect::<Vec<_>>();
+    candidates
+        .into_iter()
+        .map(|(label, data)| {
+            let score = data.plausibility(&names);
+            (label, data, score)
+        })
+        .collect()
+}
+
+impl GameData {
+    /// Scores how plausible the data is as a real save, out of `MAX_SCORE`.
+    ///
+    /// `names` are the names of every candidate being compared, including this one.
+    pub(crate) fn plausibility(&self, names: &[[char; 4]]) -> i32 {
+        let penalty = score::violation_penalty(&self.validate())
+            + score::name_penalty(&self.name)
+            + score::consensus_penalty(&self.name, names)
+            + self.economy_penalty()
+            + self.progress_penalty();
+        (MAX_SCORE - penalty).max(0)
+    }
+
+    /// Returns the progress used to place the data among other saves of the same playthrough.
+    pub(crate) fn progress(&self) -> Progress {
+        Progress {
+            experience: self.experience.into(),
+            milestones: self.progress_flags.to_vec(),
+            party: vec![],
+        }
+    }
+
+    /// Penalises gold and equipment that the experience could not have paid for.
+    fn economy_penalty(&self) -> i32 {
+        let level = stats::level(self.experience);
+        let infos = [self.weapon.info(), self.armor.info(), self.shield.info()];
+
+        let mut penalty = 0;
+        // Monsters drop roughly as much gold as they give experience.
+        let budget = STARTING_GOLD + 2 * self.experience as u32 + 500;
+        let spent = infos.iter().filter_map(|info| info.price).map(u32::from).sum::<u32>();
+        if self.gold as u32 + spent > budget {
+            penalty += 15;
+        }
+        if level < UNIQUE_EQUIPMENT_LEVEL && infos.iter().any(|info| info.unique) {
+            penalty += 15;
+        }
+        penalty
+    }
+
+    /// Penalises progress flags that the level or items make unlikely.
+    fn progress_penalty(&self) -> i32 {
+        let level = stats::level(self.experience);
+
+        let mut penalty = 0;
+        if self.progress_flags[2] && level < BOSS_LEVEL {
+            penalty += 15;
+        }
+        if self.progress_flags[3] && level < BOSS_LEVEL {
+            penalty += 10;
+        }
+        // おうじょのあい is given after rescuing the princess from the dragon.
+        if self.items.contains(&Item::GwaelinsLove) && !self.progress_flags[2] {
+            penalty += 15;
+        }
+        penalty
+    }
+}
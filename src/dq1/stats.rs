@@ -0,0 +1,131 @@
+use super::{GameData, Item, NAME_MOJI_TABLE};
+
+/// Experience required to reach each level, starting from level 1.
+pub(crate) const LEVEL_TABLE: [u16; 30] = [
+    0, 7, 23, 47, 110, 220, 450, 800, 1300, 2000, 2900, 4000, 5500, 7500, 10000, 13000, 16000,
+    19000, 22000, 26000, 30000, 34000, 38000, 42000, 46000, 50000, 54000, 58000, 62000, 65535,
+];
+
+/// Base strength, agility, max HP and max MP at each level, starting from level 1.
+pub(crate) const BASE_STATS_TABLE: [[u8; 4]; 30] = [
+    [4, 4, 15, 0],
+    [5, 4, 22, 0],
+    [7, 6, 24, 5],
+    [7, 8, 31, 16],
+    [12, 10, 35, 20],
+    [16, 10, 38, 24],
+    [18, 17, 40, 26],
+    [22, 20, 46, 29],
+    [30, 22, 50, 36],
+    [35, 31, 54, 40],
+    [40, 35, 62, 50],
+    [48, 40, 63, 58],
+    [52, 48, 70, 64],
+    [60, 55, 78, 70],
+    [68, 64, 86, 72],
+    [72, 70, 92, 95],
+    [72, 78, 100, 100],
+    [85, 84, 115, 108],
+    [87, 86, 130, 115],
+    [92, 88, 138, 128],
+    [95, 90, 149, 135],
+    [97, 90, 158, 146],
+    [99, 94, 165, 153],
+    [103, 98, 170, 161],
+    [113, 100, 174, 161],
+    [117, 105, 180, 168],
+    [125, 107, 189, 175],
+    [130, 115, 195, 180],
+    [135, 120, 200, 190],
+    [140, 130, 210, 200],
+];
+
+/// Spells with the level at which they are learned.
+pub(crate) const SPELL_TABLE: [(u8, &str); 10] = [
+    (3, "ホイミ"),
+    (4, "ギラ"),
+    (7, "ラリホー"),
+    (9, "レミーラ"),
+    (10, "マホトーン"),
+    (12, "リレミト"),
+    (13, "ルーラ"),
+    (15, "トヘロス"),
+    (17, "ベホイミ"),
+    (19, "ベギラマ"),
+];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Stats {
+    pub level: u8,
+    pub max_hp: u8,
+    pub max_mp: u8,
+    pub strength: u8,
+    pub agility: u8,
+    pub attack: u8,
+    pub defence: u8,
+    pub spells: Vec<&'static str>,
+}
+
+impl Stats {
+    /// Lists every derived value as a `(field, value)` pair.
+    pub(crate) fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("level", self.level.to_string()),
+            ("hp", self.max_hp.to_string()),
+            ("mp", self.max_mp.to_string()),
+            ("strength", self.strength.to_string()),
+            ("agility", self.agility.to_string()),
+            ("attack", self.attack.to_string()),
+            ("defence", self.defence.to_string()),
+            ("spells", self.spells.join(",")),
+        ]
+    }
+}
+
+/// Returns the level reached with the given amount of experience.
+pub(crate) fn level(experience: u16) -> u8 {
+    LEVEL_TABLE.iter().take_while(|&&threshold| threshold <= experience).count() as u8
+}
+
+/// Returns the growth pattern derived from the name, as the sum of its
+/// character codes modulo 16. The 2 least significant bits select which
+/// stats grow slowly, and the 2 most significant bits give a bonus added
+/// to the slow stats.
+pub(crate) fn growth_pattern(name: &[char; 4]) -> u8 {
+    let sum: usize =
+        name.iter().map(|&c| NAME_MOJI_TABLE.iter().position(|&moji| moji == c).unwrap()).sum();
+    (sum % 16) as u8
+}
+
+impl GameData {
+    pub(crate) fn stats(&self) -> Stats {
+        let level = level(self.experience);
+        let [strength, agility, max_hp, max_mp] = BASE_STATS_TABLE[level as usize - 1];
+
+        // Slow stats are reduced to 90%, plus the bonus, unless they are still zero.
+        let pattern = growth_pattern(&self.name);
+        let bonus = pattern >> 2;
+        let slow = |stat: u8| if stat == 0 { 0 } else { (stat as u16 * 9 / 10) as u8 + bonus };
+        let (strength, max_hp) =
+            if pattern & 0b01 == 0 { (slow(strength), max_hp) } else { (strength, slow(max_hp)) };
+        let (agility, max_mp) =
+            if pattern & 0b10 == 0 { (slow(agility), max_mp) } else { (agility, slow(max_mp)) };
+
+        let mut attack = strength + self.weapon.info().attack;
+        if self.progress_flags[1] {
+            attack += Item::FightersRing.info().attack;
+        }
+        let mut defence = agility / 2 + self.armor.info().defence + self.shield.info().defence;
+        if self.progress_flags[0] {
+            defence += Item::DragonsScale.info().defence;
+        }
+
+        let spells = SPELL_TABLE
+            .iter()
+            .filter(|&&(spell_level, _)| spell_level <= level)
+            .map(|&(_, spell)| spell)
+            .collect();
+
+        Stats { level, max_hp, max_mp, strength, agility, attack, defence, spells }
+    }
+}
@@ -0,0 +1,84 @@
+use super::complete::decrypt_last;
+use super::{
+    character_codes, checksum, decrypt, encode_game_data, pack, stats, GameData, JUMON_MOJI_TABLE,
+    MAX_ENCRYPTION_KEY,
+};
+use crate::complete::{pin_fields, search};
+use crate::rule;
+use crate::vanity::{covers, patterns, FREE_CHARACTERS};
+
+/// Finds up to `limit` valid passwords with the phrase at `position`, keeping the fields
+/// set by `assignments` and reaching at least `min_level` if given.
+pub(crate) fn vanity(
+    phrase: &str,
+    position: usize,
+    assignments: &[(String, String)],
+    min_level: Option<u8>,
+    limit: usize,
+) -> Result<Vec<(String, GameData)>, String> {
+    let phrase = character_codes(phrase)?;
+    let mut base = GameData::from_assignments(assignments)?;
+    let assigned = |field: &str| assignments.iter().any(|(assigned, _)| assigned == field);
+    if let Some(level) = min_level {
+        let threshold = stats::LEVEL_TABLE
+            .get((level as usize).wrapping_sub(1))
+            .ok_or_else(|| format!("Invalid level (expected 1 to {})", stats::LEVEL_TABLE.len()))?;
+        if !assigned("experience") {
+            base.experience = *threshold;
+        }
+    }
+
+    // Characters are packed last first
+    let fixed = |label: &str| assignments.iter().any(|(field, _)| covers(field, label));
+    let pins = pin_fields(&base.layout(), 20, fixed, |bit| (19 - bit as usize / 6, 5 - bit % 6));
+    let expected = base.fields();
+    let keeps_fields = |data: &GameData| {
+        let actual = data.fields();
+        assignments.iter().all(|(field, _)| {
+            let value = |fields: &[(&str, String)]| {
+                fields.iter().find(|(name, _)| name == field).map(|(_, value)| value.clone())
+            };
+            // The checksum is always recomputed
+            field == "checksum" || value(&expected) == value(&actual)
+        })
+    };
+
+    // Each encryption key gives a different base password to place the phrase in
+    let mut found: Vec<(String, GameData)> = vec![];
+    for free_characters in FREE_CHARACTERS {
+        if !found.is_empty() {
+            break;
+        }
+        for key in 0..=MAX_ENCRYPTION_KEY {
+            let base_codes =
+                character_codes(&encode_game_data(&GameData { encryption_key: key, ..base }))?;
+            for pattern in patterns(&base_codes, &phrase, position, &pins, free_characters)? {
+                search(&pattern, &pins, decrypt_last, &mut |codes| {
+                    let bytes = pack(&decrypt(codes));
+                    if found.len() >= limit
+                        || checksum(&bytes[..bytes.len() - 1]) != bytes[bytes.len() - 1]
+                    {
+                        return;
+                    }
+                    let data = GameData::from_bytes(&bytes);
+                    if rule::has_errors(&data.validate())
+                        || !keeps_fields(&data)
+                        || min_level.is_some_and(|level| stats::level(data.experience) < level)
+                    {
+                        return;
+                    }
+                    let password: String =
+                        codes.iter().map(|&code| JUMON_MOJI_TABLE[code as usize]).collect();
+                    if !found.iter().any(|(other, _)| *other == password) {
+                        found.push((password, data));
+                    }
+                });
+                let widened = free_characters != FREE_CHARACTERS[0];
+                if found.len() >= limit || widened && !found.is_empty() {
+                    return Ok(found);
+                }
+            }
+        }
+    }
+    Ok(found)
+}
@@ -1,8 +1,28 @@
 use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter};
-use colored::Colorize;
 use prettytable::{row, Cell, Row, Table};
 use std::io::ErrorKind::InvalidData;
 
+use crate::input;
+use crate::item::CatalogueItem;
+use crate::keypad;
+use crate::rule;
+
+mod complete;
+mod diff;
+mod explain;
+mod items;
+mod ram;
+mod rules;
+mod score;
+mod stats;
+mod vanity;
+
+pub(crate) use complete::complete;
+pub(crate) use explain::explain;
+pub(crate) use items::{tabulate_catalogue, Slot};
+pub(crate) use score::score_candidates;
+pub(crate) use vanity::vanity;
+
 pub(crate) const JUMON_MOJI_TABLE: [char; 64] = [
     'あ', 'い', 'う', 'え', 'お', 'か', 'き', 'く', 'け', 'こ', 'さ', 'し', 'す', 'せ', 'そ', 'た',
     'ち', 'つ', 'て', 'と', 'な', 'に', 'ぬ', 'ね', 'の', 'は', 'ひ', 'ふ', 'へ', 'ほ', 'ま', 'み',
@@ -17,73 +37,6 @@ pub(crate) const NAME_MOJI_TABLE: [char; 64] = [
     'ら', 'り', 'る', 'れ', 'ろ', 'わ', 'を', 'ん', 'っ', 'ゃ', 'ゅ', 'ょ', '゛', '゜', '　', '\0',
 ];
 
-pub(crate) const ITEM_TABLE: [&str; 64] = [
-    "（なし）",
-    "ひのきのぼう",
-    "せいなるナイフ",
-    "まどうしのつえ",
-    "いかずちのつえ",
-    "こんぼう",
-    "どうのつるぎ",
-    "くさりがま",
-    "てつのやり",
-    "はやぶさのけん",
-    "はがねのつるぎ",
-    "おおかなずち",
-    "はかいのつるぎ",
-    "ドラゴンキラー",
-    "ひかりのつるぎ",
-    "ロトのつるぎ",
-    "いなずまのけん",
-    "ぬののふく",
-    "みかわしのふく",
-    "みずのはごろも",
-    "ミンクのコート",
-    "かわのよろい",
-    "くさりかたびら",
-    "あくまのよろい",
-    "まほうのよろい",
-    "はがねのよろい",
-    "ガイアのよろい",
-    "ロトのよろい",
-    "かわのたて",
-    "ちからのたて",
-    "はがねのたて",
-    "しにがみのたて",
-    "ロトのたて",
-    "ふしぎなぼうし",
-    "てつかぶと",
-    "ロトのかぶと",
-    "ロトのしるし",
-    "ふねのざいほう",
-    "つきのかけら",
-    "ルビスのまもり",
-    "じゃしんのぞう",
-    "せかいじゅのは",
-    "やまびこのふえ",
-    "ラーのかがみ",
-    "あまつゆのいと",
-    "せいなりおりき",
-    "かぜのマント",
-    "あくまのしっぽ",
-    "まよけのすず",
-    "ふっかつのたま",
-    "ゴールドカード",
-    "ふくびきけん",
-    "せいすい",
-    "キメラのつばさ",
-    "みみせん",
-    "きんのかぎ",
-    "ぎんのかぎ",
-    "ろうやのかぎ",
-    "すいもんのかぎ",
-    "どくけしそう",
-    "やくそう",
-    "いのりのゆびわ",
-    "しのオルゴール",
-    "あぶないみずぎ",
-];
-
 pub(crate) const LOCATION_TABLE: [&str; 7] = [
     "ローレシア",
     "サマルトリア",
@@ -106,14 +59,72 @@ pub(crate) const PROGRESS_FLAG_TABLE: [&str; 7] = [
     "ゆうしゃのいずみ到着済み",
 ];
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Member {
+    Hero,
+    Prince,
+    Princess,
+}
+
+pub(crate) const MEMBERS: [Member; 3] = [Member::Hero, Member::Prince, Member::Princess];
+
+impl Member {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Member::Hero => "Hero",
+            Member::Prince => "Prince",
+            Member::Princess => "Princess",
+        }
+    }
+
+    /// Prefix of the member's fields in `GameData::fields`.
+    pub(crate) fn field_prefix(self) -> &'static str {
+        match self {
+            Member::Hero => "hero",
+            Member::Prince => "prince",
+            Member::Princess => "princess",
+        }
+    }
+}
+
+/// Maximum length of a password, in characters.
+pub(crate) const MAX_JUMON_LENGTH: usize = 52;
+
+/// Largest encryption key; each key gives a different password for the same data.
+pub(crate) const MAX_ENCRYPTION_KEY: u8 = 0b1111;
+
+/// Numeric fields that can be maximised, with the largest value each can hold.
+pub(crate) const OBJECTIVES: [(&str, u32); 4] = [
+    ("gold", u16::MAX as u32),
+    ("hero.experience", 0xF_FFFF),
+    ("prince.experience", 0xF_FFFF),
+    ("princess.experience", 0xF_FFFF),
+];
+
+/// The kana grid of the password screen, in blocks of five columns by consonant row.
+pub(crate) const PASSWORD_GRID: keypad::Grid = keypad::Grid {
+    rows: &[
+        "あいうえおはひふへほがぎぐげご",
+        "かきくけこまみむめもざじずぜぞ",
+        "さしすせそや　ゆ　よばびぶべぼ",
+        "たちつてとらりるれろぱぴぷぺぽ",
+        "なにぬねのわ　　　　　　　　　",
+    ],
+    end: (4, 10),
+    wrap: true,
+};
+
+/// Sizes of the groups of characters in each row of the password as the game shows it.
+pub(crate) const PASSWORD_LAYOUT: [usize; 4] = [3, 3, 3, 3];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct GameData {
     // Player name: 4 characters (from `NAME_MOJI_TABLE`, 6 bits each)
     pub hero_name: [char; 4],
-    // Item IDs: 8 slots, 4 bits each
-    pub hero_items: [u8; 8],
-    pub prince_items: [u8; 8],
-    pub princess_items: [u8; 8],
+    // Item IDs: 8 slots, 6 bits each, plus 1 bit if equipped
+    pub hero_items: [Slot; 8],
+    pub prince_items: [Slot; 8],
+    pub princess_items: [Slot; 8],
     // Experience: 20 bits
     pub hero_experience: u32,
     pub prince_experience: u32,
@@ -135,6 +146,28 @@ pub(crate) struct GameData {
     pub checksum: u16,
 }
 
+impl Default for GameData {
+    fn default() -> Self {
+        Self {
+            hero_name: ['　'; 4],
+            hero_items: [Slot::default(); 8],
+            prince_items: [Slot::default(); 8],
+            princess_items: [Slot::default(); 8],
+            hero_experience: 0,
+            prince_experience: 0,
+            princess_experience: 0,
+            prince_flag: false,
+            princess_flag: false,
+            gold: 0,
+            location: 0,
+            progress_flags: [false; 7],
+            crests: [false; 5],
+            encryption_key: 0,
+            checksum: 0,
+        }
+    }
+}
+
 impl GameData {
     pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, std::io::Error> {
         let mut data = Self::default();
@@ -186,7 +219,8 @@ impl GameData {
 
         let item_count = reader.read::<u8>(4)?;
         for i in 0..item_count {
-            *data.hero_items.get_mut(i as usize).ok_or(InvalidData)? = reader.read::<u8>(7)?;
+            *data.hero_items.get_mut(i as usize).ok_or(InvalidData)? =
+                Slot::from_byte(reader.read::<u8>(7)?);
         }
 
         data.prince_flag = reader.read::<u8>(1)? != 0;
@@ -197,7 +231,7 @@ impl GameData {
             let item_count = reader.read::<u8>(4)?;
             for i in 0..item_count {
                 *data.prince_items.get_mut(i as usize).ok_or(InvalidData)? =
-                    reader.read::<u8>(7)?;
+                    Slot::from_byte(reader.read::<u8>(7)?);
             }
 
             data.princess_flag = reader.read::<u8>(1)? != 0;
@@ -215,52 +249,271 @@ impl GameData {
                     // read the 2 least significant bits, and substitute them
                     // with the bits moved earlier in the stream if it fails.
                     let high_bits = reader.read::<u8>(5)?;
-                    *data.princess_items.get_mut(i as usize).ok_or(InvalidData)? = high_bits << 2
-                        | match reader.read::<u8>(2) {
-                            Ok(val) => val,
-                            Err(_) => final_bits,
-                        };
+                    let low_bits = match reader.read::<u8>(2) {
+                        Ok(val) => val,
+                        Err(_) => final_bits,
+                    };
+                    *data.princess_items.get_mut(i as usize).ok_or(InvalidData)? =
+                        Slot::from_byte(high_bits << 2 | low_bits);
                 }
             }
         }
 
         Ok(data)
     }
-}
 
-pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
-    // Convert input unicode characters to their corresponding 6-bit character codes
-    let jumon_chars: Vec<u8> = input
-        .chars()
-        .map(|c| {
-            JUMON_MOJI_TABLE
-                .iter()
-                .position(|&moji| moji == c)
-                .map(|index| index as u8)
-                .ok_or_else(|| format!("Unsupported input character: {}", c))
+    /// Returns whether the member is part of the party.
+    pub(crate) fn is_present(&self, member: Member) -> bool {
+        match member {
+            Member::Hero => true,
+            Member::Prince => self.prince_flag,
+            Member::Princess => self.prince_flag && self.princess_flag,
+        }
+    }
+
+    /// Returns the members that are part of the party.
+    pub(crate) fn members(&self) -> Vec<Member> {
+        MEMBERS.iter().copied().filter(|&member| self.is_present(member)).collect()
+    }
+
+    pub(crate) fn experience(&self, member: Member) -> u32 {
+        match member {
+            Member::Hero => self.hero_experience,
+            Member::Prince => self.prince_experience,
+            Member::Princess => self.princess_experience,
+        }
+    }
+
+    pub(crate) fn items(&self, member: Member) -> &[Slot; 8] {
+        match member {
+            Member::Hero => &self.hero_items,
+            Member::Prince => &self.prince_items,
+            Member::Princess => &self.princess_items,
+        }
+    }
+
+    pub(crate) fn items_mut(&mut self, member: Member) -> &mut [Slot; 8] {
+        match member {
+            Member::Hero => &mut self.hero_items,
+            Member::Prince => &mut self.prince_items,
+            Member::Princess => &mut self.princess_items,
+        }
+    }
+
+    /// Lists the fields in the order they are packed, as `(label, bit count, value)`.
+    pub(crate) fn layout(&self) -> Vec<(String, u32, u32)> {
+        let name =
+            self.hero_name.map(|c| NAME_MOJI_TABLE.iter().position(|&moji| moji == c).unwrap());
+        let field = |label: &str, bits: u32, value: u32| (label.to_string(), bits, value);
+
+        // Collect the fields first, since the total length decides whether
+        // the final bits need to be relocated.
+        let mut fields = vec![
+            field("checksum (bits 0-4)", 5, self.checksum as u32 & 0b1_1111),
+            field("location", 3, self.location as u32),
+            field("name[2]", 6, name[2] as u32),
+            field("name[1] (bits 4-5)", 2, (name[1] >> 4) as u32 & 0b11),
+            field("gold (high)", 8, self.gold as u32 >> 8),
+            field("name[1] (bits 1-2)", 2, (name[1] >> 1) as u32 & 0b11),
+            field("name[0]", 6, name[0] as u32),
+            field("gold (low)", 8, self.gold as u32 & 0xFF),
+            field("name[1] (bit 0)", 1, name[1] as u32 & 1),
+            field("name[3]", 6, name[3] as u32),
+            field("name[1] (bit 3)", 1, (name[1] >> 3) as u32 & 1),
+            field("key (bit 0)", 1, self.encryption_key as u32 & 1),
+        ];
+        for (index, &flag) in self.progress_flags.iter().enumerate() {
+            fields.push((format!("flags[{}]", index), 1, flag as u32));
+        }
+
+        fields.push(field("key (bits 1-3)", 3, self.encryption_key as u32 >> 1));
+        for (index, &crest) in self.crests.iter().enumerate() {
+            fields.push((format!("crests[{}]", index), 1, crest as u32));
+        }
+
+        let final_bits_index = fields.len();
+        fields.push(field("final bits", 2, 0));
+        fields.push(field("checksum (bits 5-10)", 6, self.checksum as u32 >> 5));
+
+        for member in MEMBERS {
+            if !self.is_present(member) {
+                break;
+            }
+            if member != Member::Hero {
+                fields.push((member.field_prefix().to_string(), 1, 1));
+            }
+
+            let prefix = member.field_prefix();
+            let experience = self.experience(member);
+            fields.push((format!("{}.experience (low)", prefix), 16, experience & 0xFFFF));
+            fields.push((format!("{}.experience (high)", prefix), 4, experience >> 16 & 0b1111));
+
+            let items = self.items(member);
+            let item_count = item_count(items);
+            fields.push((format!("{}.items (count)", prefix), 4, item_count as u32));
+            for (index, slot) in items[..item_count].iter().enumerate() {
+                // Split each item ID like the last princess item ID is read.
+                let label = format!("{}.items[{}]", prefix, index);
+                fields.push((format!("{} (high)", label), 5, slot.to_byte() as u32 >> 2));
+                fields.push((format!("{} (low)", label), 2, slot.to_byte() as u32 & 0b11));
+            }
+        }
+        // The flag of the first absent member ends the data.
+        if let Some(&member) = MEMBERS.iter().find(|&&member| !self.is_present(member)) {
+            fields.push((member.field_prefix().to_string(), 1, 0));
+        }
+
+        // If the data does not fit in the longest password, move the 2 least
+        // significant bits of the last item ID into the final bits.
+        let total_bits: u32 = fields.iter().map(|&(_, bits, _)| bits).sum();
+        if total_bits as usize > MAX_JUMON_LENGTH * 6 {
+            let (label, _, value) = fields.pop().unwrap();
+            fields[final_bits_index] = (format!("final bits ({})", label), 2, value);
+        }
+
+        fields
+    }
+
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+        for (_, bits, value) in self.layout() {
+            writer.write(bits, value).unwrap();
+        }
+        writer.byte_align().unwrap();
+
+        writer.into_writer()
+    }
+
+    /// Describes how the passwords for two game states differ, or returns `None` if
+    /// they differ in anything other than the encryption key, checksum or unused bits.
+    pub(crate) fn state_difference(&self, other: &GameData) -> Option<&'static str> {
+        let strip = |data: &GameData| GameData { encryption_key: 0, checksum: 0, ..*data };
+        if strip(self) != strip(other) {
+            return None;
+        }
+        Some(match (self.encryption_key != other.encryption_key, self.checksum != other.checksum) {
+            (true, true) => "key and checksum",
+            (true, false) => "key only",
+            (false, true) => "checksum only",
+            (false, false) => "unused bits only",
         })
-        .collect::<Result<Vec<_>, _>>()?;
+    }
 
-    // Decrypt characters.
-    let mut decrypted = vec![jumon_chars[0]];
-    let key = ((jumon_chars[0] & 0b0110) >> 1) + 1;
-    for (&prev, &cur) in jumon_chars.iter().zip(jumon_chars.iter().skip(1)) {
-        decrypted.push(cur.wrapping_sub(prev).wrapping_sub(key) & 0b0011_1111);
+    /// Replaces the stored checksum with the one matching the rest of the data.
+    pub(crate) fn update_checksum(&mut self) {
+        self.checksum = checksum(&self.to_bytes());
     }
 
-    // Pack characters into bytes
-    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
-    for &character in decrypted.iter() {
-        writer.write(6, character).unwrap();
+    /// Builds game data from `field=value` assignments, starting from the defaults.
+    pub(crate) fn from_assignments(assignments: &[(String, String)]) -> Result<Self, String> {
+        let mut data = Self::default();
+        for (field, value) in assignments.iter() {
+            data.set_field(field, value)?;
+        }
+        Ok(data)
+    }
+
+    /// Sets a single field from its textual representation, as used by `--set` and state files.
+    pub(crate) fn set_field(&mut self, field: &str, value: &str) -> Result<(), String> {
+        match field {
+            "name" => self.hero_name = input::parse_game_name(value, &NAME_MOJI_TABLE)?,
+            "location" => {
+                self.location = match LOCATION_TABLE.iter().position(|&location| location == value)
+                {
+                    Some(index) => index as u8,
+                    None => input::parse_number(value, LOCATION_TABLE.len() as u32 - 1)? as u8,
+                }
+            }
+            "gold" => self.gold = input::parse_number(value, u16::MAX as u32)? as u16,
+            "hero.experience" => self.hero_experience = input::parse_number(value, 0xF_FFFF)?,
+            "hero.items" => self.hero_items = parse_items(value)?,
+            "prince" => self.prince_flag = input::parse_bool(value)?,
+            "prince.experience" => self.prince_experience = input::parse_number(value, 0xF_FFFF)?,
+            "prince.items" => self.prince_items = parse_items(value)?,
+            "princess" => self.princess_flag = input::parse_bool(value)?,
+            "princess.experience" => {
+                self.princess_experience = input::parse_number(value, 0xF_FFFF)?
+            }
+            "princess.items" => self.princess_items = parse_items(value)?,
+            "crests" => self.crests = input::parse_flag_array(value)?,
+            "flags" => self.progress_flags = input::parse_flag_array(value)?,
+            "key" => {
+                self.encryption_key = input::parse_number(value, MAX_ENCRYPTION_KEY as u32)? as u8
+            }
+            "checksum" => self.checksum = input::parse_number(value, 0b111_1111_1111)? as u16,
+            _ => return Err(format!("Unknown DQ2 field: {}", field)),
+        }
+        Ok(())
+    }
+
+    /// Lists every field as a `(field, value)` pair accepted by `set_field`.
+    pub(crate) fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.hero_name.iter().collect()),
+            ("location", self.location.to_string()),
+            ("gold", self.gold.to_string()),
+            ("hero.experience", self.hero_experience.to_string()),
+            ("hero.items", format_items(&self.hero_items)),
+            ("prince", (self.prince_flag as u8).to_string()),
+            ("prince.experience", self.prince_experience.to_string()),
+            ("prince.items", format_items(&self.prince_items)),
+            ("princess", (self.princess_flag as u8).to_string()),
+            ("princess.experience", self.princess_experience.to_string()),
+            ("princess.items", format_items(&self.princess_items)),
+            ("crests", input::format_flags(&self.crests)),
+            ("flags", input::format_flags(&self.progress_flags)),
+            ("key", self.encryption_key.to_string()),
+            ("checksum", self.checksum.to_string()),
+        ]
+    }
+}
+
+/// Returns the number of inventory slots used, up to the last occupied slot.
+pub(crate) fn item_count(items: &[Slot; 8]) -> usize {
+    items.iter().rposition(|&slot| slot != Slot::default()).map_or(0, |i| i + 1)
+}
+
+/// Parses a comma-separated list of item names or IDs, prefixed with `E` if equipped.
+fn parse_items(input: &str) -> Result<[Slot; 8], String> {
+    let slots = input
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(Slot::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if slots.len() > 8 {
+        return Err(format!("Too many items (at most 8): {}", input));
     }
-    let mut input_bytes = writer.into_writer();
 
-    // Assemble the expected checksum from the first and ninth bytes
-    let checksum_bytes = (input_bytes[0] & 0b1111_1000, input_bytes[8] & 0b0011_1111);
-    let expected = (checksum_bytes.0 as u16) >> 3 | (checksum_bytes.1 as u16) << 5;
-    // Clear the checksum bits before validating the checksum
-    input_bytes[0] ^= checksum_bytes.0;
-    input_bytes[8] ^= checksum_bytes.1;
+    let mut items = [Slot::default(); 8];
+    items[..slots.len()].copy_from_slice(&slots);
+    Ok(items)
+}
+
+fn format_items(items: &[Slot; 8]) -> String {
+    slot_names(items).join(",")
+}
+
+/// Names the items up to the last occupied slot, prefixed with `E` if equipped.
+pub(crate) fn slot_names(items: &[Slot; 8]) -> Vec<String> {
+    items[..item_count(items)]
+        .iter()
+        .map(|slot| format!("{}{}", if slot.equipped { "E" } else { "" }, slot.item.name()))
+        .collect()
+}
+
+/// Clears the checksum bits of packed bytes, leaving only the data.
+fn data_bits(bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    bytes[0] &= 0b0000_0111;
+    bytes[8] &= 0b1100_0000;
+    bytes
+}
+
+/// Calculates the 11-bit checksum of the data bytes, ignoring the checksum bits.
+fn checksum(bytes: &[u8]) -> u16 {
+    // Clear the checksum bits before calculating the checksum
+    let input_bytes = data_bits(bytes);
 
     // Calculate the correct checksum (11-bit CRC, unknown if the format is standard)
     let mut crc = input_bytes.len() as u16 * 0x0101;
@@ -276,50 +529,182 @@ pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
         }
     }
     // Truncate the computed CRC to 11 bits
-    crc &= 0b0000_0111_1111_1111;
+    crc & 0b0000_0111_1111_1111
+}
+
+/// Converts password characters to their 6-bit character codes.
+fn character_codes(input: &str) -> Result<Vec<u8>, String> {
+    input
+        .chars()
+        .map(|c| {
+            JUMON_MOJI_TABLE
+                .iter()
+                .position(|&moji| moji == c)
+                .map(|index| index as u8)
+                .ok_or_else(|| format!("Unsupported input character: {}", c))
+        })
+        .collect()
+}
+
+/// Decrypts character codes into the 6-bit values they carry.
+fn decrypt(jumon_chars: &[u8]) -> Vec<u8> {
+    let mut decrypted = vec![jumon_chars[0]];
+    let key = ((jumon_chars[0] & 0b0110) >> 1) + 1;
+    for (&prev, &cur) in jumon_chars.iter().zip(jumon_chars.iter().skip(1)) {
+        decrypted.push(cur.wrapping_sub(prev).wrapping_sub(key) & 0b0011_1111);
+    }
+    decrypted
+}
+
+/// Packs decrypted characters into bytes, first character first.
+fn pack(decrypted: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for &character in decrypted.iter() {
+        writer.write(6, character).unwrap();
+    }
+    writer.into_writer()
+}
 
-    // Set the checksum bytes back to their original values
-    input_bytes[0] ^= checksum_bytes.0;
-    input_bytes[8] ^= checksum_bytes.1;
+/// Assembles the checksum stored in the first and ninth bytes.
+fn stored_checksum(bytes: &[u8]) -> u16 {
+    (bytes[0] as u16) >> 3 | (bytes[8] as u16 & 0b0011_1111) << 5
+}
+
+pub(crate) fn decode_jumon(input: &str) -> Result<Vec<u8>, String> {
+    let input_bytes = pack(&decrypt(&character_codes(input)?));
 
     // Confirm that the CRC is correct
-    if (crc & 0b0000_0111_1111_1111) == expected {
+    if checksum(&input_bytes) == stored_checksum(&input_bytes) {
         Ok(input_bytes)
     } else {
         Err("Invalid CRC".to_string())
     }
 }
 
-pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) -> String {
+/// Decodes a password into game data without checking its checksum.
+pub(crate) fn decode_unchecked(input: &str) -> Result<GameData, String> {
+    let codes = character_codes(input)?;
+    if !(18..=MAX_JUMON_LENGTH).contains(&codes.len()) {
+        return Err("DQ2 passwords are 18 to 52 characters long.".to_string());
+    }
+    GameData::from_bytes(&pack(&decrypt(&codes))).map_err(|err| err.to_string())
+}
+
+/// Decrypts and packs a password into bytes without checking its length or checksum.
+///
+/// The padding bits of the last character are dropped, so that `encode_jumon` gives back
+/// a password of the same length.
+pub(crate) fn packed_bytes(input: &str) -> Result<Vec<u8>, String> {
+    let codes = character_codes(input)?;
+    if !(18..=MAX_JUMON_LENGTH).contains(&codes.len()) {
+        return Err("DQ2 passwords are 18 to 52 characters long.".to_string());
+    }
+    let mut bytes = pack(&decrypt(&codes));
+    bytes.truncate(codes.len() * 6 / 8);
+    Ok(bytes)
+}
+
+/// Encodes the packed bytes of a password, as given by `packed_bytes`.
+pub(crate) fn password_from_bytes(bytes: &[u8]) -> Result<String, String> {
+    let lengths = 18 * 6 / 8..=MAX_JUMON_LENGTH * 6 / 8;
+    if !lengths.contains(&bytes.len()) {
+        return Err(format!(
+            "DQ2 passwords pack into {} to {} bytes, not {}.",
+            lengths.start(),
+            lengths.end(),
+            bytes.len()
+        ));
+    }
+    Ok(encode_jumon(bytes))
+}
+
+pub(crate) fn encode_jumon(bytes: &[u8]) -> String {
+    // Unpack bytes into characters, padding the last character with zeroes
+    let character_count = (bytes.len() * 8).div_ceil(6);
+    let mut padded_bytes = bytes.to_vec();
+    padded_bytes.push(0);
+    let mut reader = BitReader::endian(padded_bytes.as_slice(), BigEndian);
+    let mut decrypted = vec![];
+    for _ in 0..character_count {
+        decrypted.push(reader.read::<u8>(6).unwrap());
+    }
+
+    encrypt(&decrypted)
+}
+
+/// Encrypts 6-bit values into password characters, in the reverse order of `decrypt`.
+fn encrypt(decrypted: &[u8]) -> String {
+    let mut output = String::with_capacity(decrypted.len());
+    let key = ((decrypted[0] & 0b0110) >> 1) + 1;
+    let mut prev = decrypted[0];
+    output.push(JUMON_MOJI_TABLE[prev as usize]);
+    for &character in decrypted.iter().skip(1) {
+        prev = character.wrapping_add(prev).wrapping_add(key) & 0b0011_1111;
+        output.push(JUMON_MOJI_TABLE[prev as usize]);
+    }
+    output
+}
+
+/// Re-encodes the data of a password with its checksum recomputed, keeping every data
+/// bit, as the equivalent password that differs from the input in the fewest characters.
+///
+/// Each character chains into the decryption of the next, so the characters after the
+/// ones carrying the checksum may change too.
+pub(crate) fn repair_checksum(input: &str) -> Result<String, String> {
+    let data = decode_unchecked(input)?;
+    let changes = |password: &String| {
+        let same = password.chars().zip(input.chars()).filter(|(a, b)| a == b).count();
+        password.chars().count().max(input.chars().count()) - same
+    };
+    Ok(equivalent_passwords(&data)
+        .into_iter()
+        .map(|(_, password)| password)
+        .min_by_key(changes)
+        .unwrap())
+}
+
+/// Encodes game data into a valid password, recomputing its checksum.
+pub(crate) fn encode_game_data(data: &GameData) -> String {
+    let mut data = *data;
+    data.update_checksum();
+    encode_jumon(&data.to_bytes())
+}
+
+/// Encodes the game data with every encryption key, giving all equivalent passwords.
+pub(crate) fn equivalent_passwords(data: &GameData) -> Vec<(u8, String)> {
+    (0..=MAX_ENCRYPTION_KEY)
+        .map(|key| (key, encode_game_data(&GameData { encryption_key: key, ..*data })))
+        .collect()
+}
+
+/// Tabulates candidates with the scores they were ranked by.
+pub(crate) fn tabulate_game_data(data: Vec<(String, GameData, i32)>, input: &str) -> String {
     // Create the table headers
     let mut table = Table::new();
     let header_row = row![
         "Player Name",
         "Location",
         "Experience",
+        "Level",
+        "Attack / Defence",
+        "Spells (Prince)",
+        "Spells (Princess)",
         "Gold",
         "Items (Hero)",
         "Items (Prince)",
         "Items (Princess)",
         "Crests",
         "Progress Flags",
-        "Checksum"
+        "Checksum",
+        "Score",
+        "Notes"
     ];
     table.add_row(header_row.clone());
 
-    // Iterate over each `(String, GameData)` tuple and add its information to the table
-    for (label, game_data) in data {
+    // Iterate over each `(String, GameData, i32)` tuple and add its information to the table
+    for (label, game_data, score) in data {
         // Set the color of any substituted characters in the label to red
-        let mut formatted_label = String::with_capacity(input.len());
-        for (index, input_character) in input.chars().enumerate() {
-            let label_character = label.char_indices().nth(index).unwrap().1;
-            if input_character == label_character {
-                formatted_label.push(input_character);
-            } else {
-                formatted_label =
-                    format!("{}{}", formatted_label, label_character.to_string().red());
-            }
-        }
+        let formatted_label = input::highlight_substitutions(&label, input);
 
         // Add the label as a single row spanning the entire table width, to keep it compact
         table.add_row(Row::new(vec![Cell::new(&formatted_label).with_hspan(header_row.len())]));
@@ -327,7 +712,11 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
         // Add the `GameData` object to a new row as individual cells
         let mut cells = Vec::new();
         cells.push(Cell::new(&game_data.hero_name.iter().collect::<String>()));
-        cells.push(Cell::new(LOCATION_TABLE[game_data.location as usize]));
+        cells.push(Cell::new(
+            &LOCATION_TABLE
+                .get(game_data.location as usize)
+                .map_or(format!("Invalid location {}", game_data.location), |l| l.to_string()),
+        ));
         cells.push(Cell::new(&format!(
             "Hero: {}\nPrince: {}\nPrincess: {}",
             game_data.hero_experience,
@@ -342,50 +731,54 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
                 "N/A".to_string()
             },
         )));
-        cells.push(Cell::new(&game_data.gold.to_string()));
+        let stats =
+            MEMBERS.map(|member| game_data.is_present(member).then(|| game_data.stats(member)));
         cells.push(Cell::new(
-            &game_data
-                .hero_items
+            &MEMBERS
+                .iter()
+                .zip(stats.iter())
+                .map(|(member, stats)| match stats {
+                    Some(stats) => format!("{}: {}", member.name(), stats.level),
+                    None => format!("{}: N/A", member.name()),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ));
+        cells.push(Cell::new(
+            &MEMBERS
                 .iter()
-                .map(|&x| {
-                    let equipped = x & 0b100_0000 != 0;
-                    if equipped { "E " } else { "  " }.to_owned()
-                        + ITEM_TABLE[(x & 0b011_1111) as usize]
+                .zip(stats.iter())
+                .map(|(member, stats)| match stats {
+                    Some(stats) => {
+                        format!("{}: {} / {}", member.name(), stats.attack, stats.defence)
+                    }
+                    None => format!("{}: N/A", member.name()),
                 })
                 .collect::<Vec<_>>()
                 .join("\n"),
         ));
-        if game_data.prince_flag {
-            cells.push(Cell::new(
-                &game_data
-                    .prince_items
-                    .iter()
-                    .map(|&x| {
-                        let equipped = x & 0b100_0000 != 0;
-                        if equipped { "E " } else { "  " }.to_owned()
-                            + ITEM_TABLE[(x & 0b011_1111) as usize]
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            ));
-        } else {
-            cells.push(Cell::new("N/A"));
+        for stats in stats[1..].iter() {
+            match stats {
+                Some(stats) => cells.push(Cell::new(&stats.spells.join("\n"))),
+                None => cells.push(Cell::new("N/A")),
+            }
         }
-        if game_data.princess_flag {
-            cells.push(Cell::new(
-                &game_data
-                    .princess_items
-                    .iter()
-                    .map(|&x| {
-                        let equipped = x & 0b100_0000 != 0;
-                        if equipped { "E " } else { "  " }.to_owned()
-                            + ITEM_TABLE[(x & 0b011_1111) as usize]
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            ));
-        } else {
-            cells.push(Cell::new("N/A"));
+        cells.push(Cell::new(&game_data.gold.to_string()));
+        for member in MEMBERS {
+            if game_data.is_present(member) {
+                cells.push(Cell::new(
+                    &game_data
+                        .items(member)
+                        .iter()
+                        .map(|slot| {
+                            if slot.equipped { "E " } else { "  " }.to_owned() + slot.item.name()
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ));
+            } else {
+                cells.push(Cell::new("N/A"));
+            }
         }
         cells.push(Cell::new(
             &game_data
@@ -411,6 +804,8 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
         cells.push(Cell::new(
             format!("{} (Key: {})", game_data.checksum, game_data.encryption_key).as_str(),
         ));
+        cells.push(Cell::new(&score.to_string()));
+        cells.push(Cell::new(&rule::format_violations(&game_data.validate())));
 
         table.add_row(Row::new(cells));
     }
@@ -418,3 +813,108 @@ pub(crate) fn tabulate_game_data(data: Vec<(String, GameData)>, input: &str) ->
     // Return the table as a `String`
     table.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::items::Item;
+    use super::*;
+
+    fn game_data(assignments: &[(&str, &str)]) -> GameData {
+        let assignments =
+            assignments.iter().map(|&(f, v)| (f.to_string(), v.to_string())).collect::<Vec<_>>();
+        GameData::from_assignments(&assignments).unwrap()
+    }
+
+    #[test]
+    fn repairs_checksum_keeping_the_data() {
+        let mut data = game_data(&[("gold", "500"), ("hero.experience", "99"), ("key", "9")]);
+        data.update_checksum();
+        let valid = encode_jumon(&data.to_bytes());
+        assert_eq!(repair_checksum(&valid).unwrap(), valid);
+
+        let changes = |a: &str, b: &str| a.chars().zip(b.chars()).filter(|(a, b)| a != b).count();
+        let broken = encode_jumon(&GameData { checksum: data.checksum ^ 1, ..data }.to_bytes());
+        assert!(decode_jumon(&broken).is_err());
+        let repaired = repair_checksum(&broken).unwrap();
+        let decoded = GameData::from_bytes(&decode_jumon(&repaired).unwrap()).unwrap();
+        assert!(decoded.state_difference(&data).is_some());
+        // The original password is one of the candidates, so no more characters change
+        assert!(changes(&repaired, &broken) <= changes(&valid, &broken));
+    }
+
+    #[test]
+    fn repair_keeps_the_data_of_a_mistyped_character() {
+        let valid = encode_game_data(&game_data(&[("gold", "500"), ("key", "3")]));
+        for position in [0, 5, 11, 17] {
+            let mut broken = valid.chars().collect::<Vec<_>>();
+            broken[position] = if broken[position] == 'あ' { 'い' } else { 'あ' };
+            let broken = broken.into_iter().collect::<String>();
+            // A mistyped character can make the data run past the end of the password
+            let Ok(written) = decode_unchecked(&broken) else {
+                assert!(repair_checksum(&broken).is_err());
+                continue;
+            };
+            let repaired = repair_checksum(&broken).unwrap();
+            let decoded = GameData::from_bytes(&decode_jumon(&repaired).unwrap()).unwrap();
+            assert!(decoded.state_difference(&written).is_some());
+        }
+    }
+    fn round_trip(data: &GameData) -> (String, GameData) {
+        let password = encode_game_data(data);
+        let decoded = GameData::from_bytes(&decode_jumon(&password).unwrap()).unwrap();
+        (password, decoded)
+    }
+
+    #[test]
+    fn round_trips_hero_alone() {
+        let data = game_data(&[
+            ("name", "ああああ"),
+            ("location", "ムーンペタ"),
+            ("gold", "1234"),
+            ("hero.experience", "5678"),
+            ("hero.items", "E1,やくそう"),
+            ("key", "5"),
+        ]);
+        let (password, decoded) = round_trip(&data);
+        assert_eq!(decoded.state_difference(&data), Some("checksum only"));
+        assert_eq!(password.chars().count(), 19);
+    }
+
+    #[test]
+    fn round_trips_full_party_with_relocated_final_bits() {
+        // 314 bits of data, so the low bits of the last item move into the final bits
+        let items = "1,2,3,4,5,6,7,63";
+        let data = game_data(&[
+            ("hero.items", items),
+            ("prince", "true"),
+            ("prince.items", items),
+            ("princess", "true"),
+            ("princess.items", items),
+        ]);
+        assert!(data.layout().iter().any(|(label, _, _)| label.starts_with("final bits (")));
+        let (password, decoded) = round_trip(&data);
+        assert_eq!(password.chars().count(), MAX_JUMON_LENGTH);
+        assert_eq!(decoded.princess_items[7].item, Item::DangerousSwimsuit);
+        assert_eq!(decoded.state_difference(&data), Some("checksum only"));
+    }
+
+    #[test]
+    fn every_equivalent_password_decodes_to_the_same_state() {
+        let data = game_data(&[
+            ("name", "ゆうてい"),
+            ("gold", "65535"),
+            ("hero.experience", "1000000"),
+            ("hero.items", "E1,E16,2"),
+            ("prince", "true"),
+            ("prince.experience", "2000"),
+        ]);
+        let passwords = equivalent_passwords(&data);
+        assert_eq!(passwords.len(), MAX_ENCRYPTION_KEY as usize + 1);
+        for (key, password) in passwords {
+            let decoded = GameData::from_bytes(&decode_jumon(&password).unwrap()).unwrap();
+            assert_eq!(decoded.encryption_key, key);
+            assert!(decoded.state_difference(&data).is_some(), "key {}", key);
+            assert_eq!(password_from_bytes(&packed_bytes(&password).unwrap()).unwrap(), password);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::items::Item;
+use super::{
+    checksum, decrypt, pack, stored_checksum, GameData, JUMON_MOJI_TABLE, MAX_JUMON_LENGTH,
+    MEMBERS, NAME_MOJI_TABLE, PROGRESS_FLAG_TABLE,
+};
+use crate::complete::{check_free_bits, parse_pattern, pin_fields, search};
+use crate::item::CatalogueItem;
+use crate::rule;
+
+/// Decrypts the last character of a partial password.
+pub(super) fn decrypt_last(codes: &[u8]) -> u8 {
+    let last = codes.len() - 1;
+    if last == 0 {
+        return codes[0];
+    }
+    let key = ((codes[0] & 0b0110) >> 1) + 1;
+    codes[last].wrapping_sub(codes[last - 1]).wrapping_sub(key) & 0b0011_1111
+}
+
+/// Lists the valid passwords of `length` characters that complete a partial password,
+/// keeping the name and progress flags if given and holding every item in `items`.
+pub(crate) fn complete(
+    input: &str,
+    length: usize,
+    name: Option<[char; 4]>,
+    progress_flags: Option<&[bool]>,
+    items: &[String],
+) -> Result<Vec<(String, GameData)>, String> {
+    if !(18..=MAX_JUMON_LENGTH).contains(&length) {
+        return Err("DQ2 passwords are 18 to 52 characters long.".to_string());
+    }
+    let pattern = parse_pattern(input, length, &JUMON_MOJI_TABLE)?;
+    let items = items.iter().map(|item| Item::parse(item)).collect::<Result<Vec<_>, _>>()?;
+
+    // Lay out the known fields, so that the search only tries characters that keep them.
+    // The name and flags come before the party data, so the party does not matter.
+    let mut known = GameData::default();
+    if let Some(name) = name {
+        if let Some(c) = name.iter().find(|c| !NAME_MOJI_TABLE.contains(c)) {
+            return Err(format!("Unsupported name character: {}", c));
+        }
+        known.hero_name = name;
+    }
+    if let Some(flags) = progress_flags {
+        known.progress_flags = flags
+            .try_into()
+            .map_err(|_| format!("DQ2 has {} progress flags.", PROGRESS_FLAG_TABLE.len()))?;
+    }
+    let fixed = |label: &str| {
+        name.is_some() && label.starts_with("name[")
+            || progress_flags.is_some() && label.starts_with("flags[")
+    };
+    let pins =
+        pin_fields(&known.layout(), pattern.len(), fixed, |bit| (bit as usize / 6, 5 - bit % 6));
+    check_free_bits(&pattern, &pins)?;
+
+    let mut completions = vec![];
+    search(&pattern, &pins, decrypt_last, &mut |codes| {
+        let bytes = pack(&decrypt(codes));
+        if checksum(&bytes) != stored_checksum(&bytes) {
+            return;
+        }
+        let Ok(data) = GameData::from_bytes(&bytes) else {
+            return;
+        };
+        let holds = |item: &Item| {
+            MEMBERS.iter().any(|&member| data.items(member).iter().any(|slot| slot.item == *item))
+        };
+        if rule::has_errors(&data.validate()) || !items.iter().all(holds) {
+            return;
+        }
+        let password = codes.iter().map(|&code| JUMON_MOJI_TABLE[code as usize]).collect();
+        completions.push((password, data));
+    });
+    Ok(completions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dq2::encode_game_data;
+
+    #[test]
+    fn finds_the_password_with_lost_trailing_characters() {
+        let assignments = [("name", "ああああ"), ("gold", "300"), ("hero.items", "E1")]
+            .map(|(field, value)| (field.to_string(), value.to_string()));
+        let password = encode_game_data(&GameData::from_assignments(&assignments).unwrap());
+        let length = password.chars().count();
+
+        // The lost end of the password is filled in up to the given length
+        let partial = password.chars().take(length - 2).collect::<String>();
+        let completions = complete(&partial, length, None, None, &[]).unwrap();
+        assert!(completions.iter().any(|(completion, _)| *completion == password));
+        assert!(complete(&partial, length - 3, None, None, &[]).is_err());
+        assert!(complete(&partial, MAX_JUMON_LENGTH + 1, None, None, &[]).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use super::{GameData, CREST_TABLE, LOCATION_TABLE, MEMBERS, PROGRESS_FLAG_TABLE};
+use crate::diff::{self, Change};
+
+impl GameData {
+    /// Lists the fields that differ from `other`, which is taken to be the later state.
+    pub(crate) fn diff(&self, other: &GameData) -> Vec<Change> {
+        let name = |data: &GameData| data.hero_name.iter().collect::<String>();
+        let location = |data: &GameData| {
+            LOCATION_TABLE
+                .get(data.location as usize)
+                .map_or(data.location.to_string(), |l| l.to_string())
+        };
+
+        let mut changes = vec![
+            diff::value("name", name(self), name(other)),
+            diff::value("location", location(self), location(other)),
+            diff::number("gold", self.gold.into(), other.gold.into()),
+        ];
+        for member in MEMBERS {
+            let prefix = member.field_prefix();
+            let (before, after) = (self.is_present(member), other.is_present(member));
+            if before != after {
+                changes.push(diff::value(
+                    prefix,
+                    if before { "present" } else { "absent" },
+                    if after { "present" } else { "absent" },
+                ));
+            }
+
+            let experience = diff::number(
+                &format!("{}.experience", prefix),
+                self.experience(member).into(),
+                other.experience(member).into(),
+            )
+            .map(|mut change| {
+                let before = member.level(self.experience(member));
+                let after = member.level(other.experience(member));
+                if before != after {
+                    change.summary = format!("{} (level {} → {})", change.summary, before, after);
+                }
+                change
+            });
+            changes.push(experience);
+
+            let (before, after) =
+                (super::slot_names(self.items(member)), super::slot_names(other.items(member)));
+            changes.push(diff::list(
+                &format!("{}.items", prefix),
+                &before.iter().map(String::as_str).collect::<Vec<_>>(),
+                &after.iter().map(String::as_str).collect::<Vec<_>>(),
+            ));
+        }
+        changes.extend([
+            diff::flags("crests", &CREST_TABLE, &self.crests, &other.crests),
+            diff::flags("flags", &PROGRESS_FLAG_TABLE, &self.progress_flags, &other.progress_flags),
+            diff::number("key", self.encryption_key.into(), other.encryption_key.into()),
+            diff::number("checksum", self.checksum.into(), other.checksum.into()),
+        ]);
+
+        changes.into_iter().flatten().collect()
+    }
+}
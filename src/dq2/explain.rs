@@ -0,0 +1,29 @@
+use super::GameData;
+use crate::explain;
+
+/// Explains how a password decodes, bit by bit, whether or not its checksum is valid.
+pub(crate) fn explain(input: &str) -> Result<String, String> {
+    let codes = super::character_codes(input)?;
+    if !(18..=super::MAX_JUMON_LENGTH).contains(&codes.len()) {
+        return Err("DQ2 passwords are 18 to 52 characters long.".to_string());
+    }
+    let decrypted = super::decrypt(&codes);
+    let bytes = super::pack(&decrypted);
+
+    let offsets = (0..codes.len()).map(|index| index * 6).collect::<Vec<_>>();
+    let fields = match GameData::from_bytes(&bytes) {
+        Ok(data) => explain::tabulate_layout(&bytes, &data.layout()),
+        Err(err) => format!("The bytes cannot be read as game data: {}\n", err),
+    };
+
+    Ok([
+        format!(
+            "Characters:\n{}",
+            explain::tabulate_characters(input, &codes, &decrypted, &offsets)
+        ),
+        format!("Bytes:\n{}", explain::tabulate_bytes(&bytes)),
+        format!("Fields:\n{}", fields),
+        explain::format_checksum(super::stored_checksum(&bytes), super::checksum(&bytes)),
+    ]
+    .join("\n"))
+}
@@ -0,0 +1,348 @@
+use prettytable::{row, Table};
+
+use super::{Member, MEMBERS};
+use crate::item::{self, CatalogueItem, Category};
+
+/// Static metadata of an item in the catalogue.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ItemInfo {
+    pub name: &'static str,
+    pub category: Category,
+    // Party members who can equip the item
+    pub equippable_by: &'static [Member],
+    pub attack: u8,
+    pub defence: u8,
+    // Shop price, if the item is sold anywhere
+    pub price: Option<u16>,
+    // Value when sold to a shop, if the item can be sold
+    pub sell_value: Option<u16>,
+    // Whether the item can only be obtained once
+    pub unique: bool,
+}
+
+const EVERYONE: &[Member] = &MEMBERS;
+const HERO: &[Member] = &[Member::Hero];
+const HERO_PRINCE: &[Member] = &[Member::Hero, Member::Prince];
+const PRINCE_PRINCESS: &[Member] = &[Member::Prince, Member::Princess];
+const PRINCESS: &[Member] = &[Member::Princess];
+const NOBODY: &[Member] = &[];
+
+/// Builds an `ItemInfo` for equipment, in field order, to keep the catalogue compact.
+const fn equipment(
+    name: &'static str,
+    category: Category,
+    equippable_by: &'static [Member],
+    attack: u8,
+    defence: u8,
+    price: Option<u16>,
+    unique: bool,
+) -> ItemInfo {
+    let sell_value = match price {
+        Some(price) => Some(price / 2),
+        None => None,
+    };
+    ItemInfo { name, category, equippable_by, attack, defence, price, sell_value, unique }
+}
+
+/// Builds an `ItemInfo` for an item that cannot be equipped.
+const fn item(
+    name: &'static str,
+    category: Category,
+    price: Option<u16>,
+    sell_value: Option<u16>,
+    unique: bool,
+) -> ItemInfo {
+    ItemInfo {
+        name,
+        category,
+        equippable_by: NOBODY,
+        attack: 0,
+        defence: 0,
+        price,
+        sell_value,
+        unique,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Item {
+    #[default]
+    Nothing,
+    CypressStick,
+    HolyKnife,
+    WizardsWand,
+    StaffOfThunder,
+    Club,
+    CopperSword,
+    ChainSickle,
+    IronSpear,
+    FalconSword,
+    BroadSword,
+    GiantHammer,
+    SwordOfDestruction,
+    DragonKiller,
+    LightSword,
+    ErdricksSword,
+    ThunderSword,
+    Clothes,
+    ClothesOfEvasion,
+    WaterFlyingCloth,
+    MinkCoat,
+    LeatherArmor,
+    ChainMail,
+    DemonArmor,
+    MagicArmor,
+    FullPlate,
+    ArmorOfGaia,
+    ErdricksArmor,
+    LeatherShield,
+    ShieldOfStrength,
+    SteelShield,
+    EvilShield,
+    ErdricksShield,
+    MysteriousHat,
+    IronHelmet,
+    ErdricksHelmet,
+    ErdricksToken,
+    ShipsTreasure,
+    MoonFragment,
+    CharmOfRubiss,
+    EvilIdol,
+    LeafOfTheWorldTree,
+    EchoingFlute,
+    MirrorOfRa,
+    DewsYarn,
+    MagicLoom,
+    CloakOfWind,
+    DevilsTail,
+    AmuletBell,
+    OrbOfRevival,
+    GoldCard,
+    LotteryTicket,
+    HolyWater,
+    Wings,
+    Earplugs,
+    GoldenKey,
+    SilverKey,
+    JailorsKey,
+    WateryGateKey,
+    Antidote,
+    Herb,
+    RingOfPrayer,
+    MusicBoxOfDeath,
+    DangerousSwimsuit,
+}
+
+const ITEM_CATALOGUE: [ItemInfo; 64] = [
+    item("（なし）", Category::Nothing, None, None, false),
+    // Weapons
+    equipment("ひのきのぼう", Category::Weapon, EVERYONE, 2, 0, Some(10), false),
+    equipment("せいなるナイフ", Category::Weapon, EVERYONE, 5, 0, Some(200), false),
+    equipment("まどうしのつえ", Category::Weapon, PRINCE_PRINCESS, 8, 0, Some(2500), false),
+    equipment("いかずちのつえ", Category::Weapon, EVERYONE, 15, 0, None, true),
+    equipment("こんぼう", Category::Weapon, HERO_PRINCE, 7, 0, Some(60), false),
+    equipment("どうのつるぎ", Category::Weapon, HERO_PRINCE, 12, 0, Some(100), false),
+    equipment("くさりがま", Category::Weapon, HERO_PRINCE, 16, 0, Some(390), false),
+    equipment("てつのやり", Category::Weapon, HERO_PRINCE, 20, 0, Some(770), false),
+    equipment("はやぶさのけん", Category::Weapon, HERO_PRINCE, 5, 0, Some(25000), false),
+    equipment("はがねのつるぎ", Category::Weapon, HERO, 30, 0, Some(1500), false),
+    equipment("おおかなずち", Category::Weapon, HERO, 45, 0, Some(4000), false),
+    equipment("はかいのつるぎ", Category::Weapon, HERO, 99, 0, None, true),
+    equipment("ドラゴンキラー", Category::Weapon, HERO, 50, 0, Some(15000), false),
+    equipment("ひかりのつるぎ", Category::Weapon, HERO, 40, 0, Some(8000), false),
+    equipment("ロトのつるぎ", Category::Weapon, HERO, 40, 0, None, true),
+    equipment("いなずまのけん", Category::Weapon, HERO, 65, 0, None, true),
+    // Armor
+    equipment("ぬののふく", Category::Armor, EVERYONE, 0, 2, Some(30), false),
+    equipment("みかわしのふく", Category::Armor, EVERYONE, 0, 15, Some(1500), false),
+    equipment("みずのはごろも", Category::Armor, PRINCE_PRINCESS, 0, 40, None, true),
+    equipment("ミンクのコート", Category::Armor, PRINCESS, 0, 25, None, true),
+    equipment("かわのよろい", Category::Armor, HERO_PRINCE, 0, 10, Some(150), false),
+    equipment("くさりかたびら", Category::Armor, HERO_PRINCE, 0, 15, Some(390), false),
+    equipment("あくまのよろい", Category::Armor, HERO_PRINCE, 0, 40, None, true),
+    equipment("まほうのよろい", Category::Armor, HERO_PRINCE, 0, 25, Some(4300), false),
+    equipment("はがねのよろい", Category::Armor, HERO, 0, 30, Some(2000), false),
+    equipment("ガイアのよろい", Category::Armor, HERO, 0, 50, None, true),
+    equipment("ロトのよろい", Category::Armor, HERO, 0, 50, None, true),
+    // Shields
+    equipment("かわのたて", Category::Shield, HERO_PRINCE, 0, 4, Some(90), false),
+    equipment("ちからのたて", Category::Shield, HERO_PRINCE, 0, 20, Some(8000), false),
+    equipment("はがねのたて", Category::Shield, HERO, 0, 10, Some(2000), false),
+    equipment("しにがみのたて", Category::Shield, HERO_PRINCE, 0, 40, None, true),
+    equipment("ロトのたて", Category::Shield, HERO, 0, 30, None, true),
+    // Helmets
+    equipment("ふしぎなぼうし", Category::Helmet, PRINCE_PRINCESS, 0, 8, None, true),
+    equipment("てつかぶと", Category::Helmet, HERO_PRINCE, 0, 10, Some(3150), false),
+    equipment("ロトのかぶと", Category::Helmet, HERO, 0, 15, None, true),
+    // Other items
+    item("ロトのしるし", Category::KeyItem, None, None, true),
+    item("ふねのざいほう", Category::KeyItem, None, None, true),
+    item("つきのかけら", Category::KeyItem, None, None, true),
+    item("ルビスのまもり", Category::KeyItem, None, None, true),
+    item("じゃしんのぞう", Category::KeyItem, None, None, true),
+    item("せかいじゅのは", Category::Consumable, None, None, true),
+    item("やまびこのふえ", Category::KeyItem, None, None, true),
+    item("ラーのかがみ", Category::KeyItem, None, None, true),
+    item("あまつゆのいと", Category::KeyItem, None, None, true),
+    item("せいなりおりき", Category::KeyItem, None, None, true),
+    item("かぜのマント", Category::KeyItem, None, None, true),
+    item("あくまのしっぽ", Category::KeyItem, None, None, true),
+    item("まよけのすず", Category::KeyItem, Some(180), Some(90), false),
+    item("ふっかつのたま", Category::KeyItem, None, None, true),
+    item("ゴールドカード", Category::KeyItem, None, None, true),
+    item("ふくびきけん", Category::Consumable, None, Some(5), false),
+    item("せいすい", Category::Consumable, Some(40), Some(20), false),
+    item("キメラのつばさ", Category::Consumable, Some(25), Some(12), false),
+    item("みみせん", Category::KeyItem, None, None, true),
+    item("きんのかぎ", Category::KeyItem, None, None, true),
+    item("ぎんのかぎ", Category::KeyItem, None, None, true),
+    item("ろうやのかぎ", Category::KeyItem, None, None, true),
+    item("すいもんのかぎ", Category::KeyItem, None, None, true),
+    item("どくけしそう", Category::Consumable, Some(10), Some(5), false),
+    item("やくそう", Category::Consumable, Some(20), Some(10), false),
+    item("いのりのゆびわ", Category::Consumable, Some(2500), Some(1250), false),
+    item("しのオルゴール", Category::KeyItem, None, None, true),
+    equipment("あぶないみずぎ", Category::Armor, PRINCESS, 0, 0, None, true),
+];
+
+impl CatalogueItem for Item {
+    const ALL: &'static [Self] = &[
+        Item::Nothing,
+        Item::CypressStick,
+        Item::HolyKnife,
+        Item::WizardsWand,
+        Item::StaffOfThunder,
+        Item::Club,
+        Item::CopperSword,
+        Item::ChainSickle,
+        Item::IronSpear,
+        Item::FalconSword,
+        Item::BroadSword,
+        Item::GiantHammer,
+        Item::SwordOfDestruction,
+        Item::DragonKiller,
+        Item::LightSword,
+        Item::ErdricksSword,
+        Item::ThunderSword,
+        Item::Clothes,
+        Item::ClothesOfEvasion,
+        Item::WaterFlyingCloth,
+        Item::MinkCoat,
+        Item::LeatherArmor,
+        Item::ChainMail,
+        Item::DemonArmor,
+        Item::MagicArmor,
+        Item::FullPlate,
+        Item::ArmorOfGaia,
+        Item::ErdricksArmor,
+        Item::LeatherShield,
+        Item::ShieldOfStrength,
+        Item::SteelShield,
+        Item::EvilShield,
+        Item::ErdricksShield,
+        Item::MysteriousHat,
+        Item::IronHelmet,
+        Item::ErdricksHelmet,
+        Item::ErdricksToken,
+        Item::ShipsTreasure,
+        Item::MoonFragment,
+        Item::CharmOfRubiss,
+        Item::EvilIdol,
+        Item::LeafOfTheWorldTree,
+        Item::EchoingFlute,
+        Item::MirrorOfRa,
+        Item::DewsYarn,
+        Item::MagicLoom,
+        Item::CloakOfWind,
+        Item::DevilsTail,
+        Item::AmuletBell,
+        Item::OrbOfRevival,
+        Item::GoldCard,
+        Item::LotteryTicket,
+        Item::HolyWater,
+        Item::Wings,
+        Item::Earplugs,
+        Item::GoldenKey,
+        Item::SilverKey,
+        Item::JailorsKey,
+        Item::WateryGateKey,
+        Item::Antidote,
+        Item::Herb,
+        Item::RingOfPrayer,
+        Item::MusicBoxOfDeath,
+        Item::DangerousSwimsuit,
+    ];
+
+    fn name(self) -> &'static str {
+        self.info().name
+    }
+}
+
+impl Item {
+    pub(crate) fn info(self) -> &'static ItemInfo {
+        &ITEM_CATALOGUE[self as usize]
+    }
+
+    pub(crate) fn can_equip(self, member: Member) -> bool {
+        self.info().equippable_by.contains(&member)
+    }
+}
+
+/// An inventory slot, holding an item that may be equipped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Slot {
+    pub item: Item,
+    pub equipped: bool,
+}
+
+impl Slot {
+    /// Splits a 7-bit item byte into its item ID and equipped bit.
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        Slot { item: Item::from_id(byte & 0b011_1111).unwrap(), equipped: byte & 0b100_0000 != 0 }
+    }
+
+    pub(crate) fn to_byte(self) -> u8 {
+        self.item.id() | (self.equipped as u8) << 6
+    }
+
+    /// Parses an item from its name or ID, prefixed with `E` if equipped.
+    pub(crate) fn parse(input: &str) -> Result<Self, String> {
+        match input.strip_prefix('E') {
+            Some(item) => Ok(Slot { item: Item::parse(item.trim())?, equipped: true }),
+            None => Ok(Slot { item: Item::parse(input)?, equipped: false }),
+        }
+    }
+}
+
+/// Formats every item in the catalogue as a table.
+pub(crate) fn tabulate_catalogue() -> String {
+    let mut table = Table::new();
+    table.add_row(row![
+        "ID",
+        "Name",
+        "Category",
+        "Equippable By",
+        "Attack",
+        "Defence",
+        "Price",
+        "Sell Value",
+        "Unique"
+    ]);
+
+    for &item in Item::ALL.iter().skip(1) {
+        let info = item.info();
+        table.add_row(row![
+            item.id(),
+            info.name,
+            info.category.name(),
+            info.equippable_by.iter().map(|member| member.name()).collect::<Vec<_>>().join(", "),
+            info.attack,
+            info.defence,
+            item::format_price(info.price),
+            item::format_price(info.sell_value),
+            if info.unique { "○" } else { "" }
+        ]);
+    }
+
+    table.to_string()
+}
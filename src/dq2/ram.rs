@@ -0,0 +1,107 @@
+use super::{GameData, Member, Slot, LOCATION_TABLE, MEMBERS, NAME_MOJI_TABLE};
+use crate::savestate::Ram;
+
+/// Hero name, as 4 codes of `NAME_MOJI_TABLE`.
+const NAME: usize = 0x0600;
+/// Whether the prince (bit 0) and the princess (bit 1) have joined the party.
+const PARTY: usize = 0x0610;
+/// Gold, low byte first.
+const GOLD: usize = 0x0611;
+/// Index in `LOCATION_TABLE` of the place the party revives at.
+const LOCATION: usize = 0x0613;
+/// Progress flags and crests, one bit each in table order from bit 0.
+const PROGRESS_FLAGS: usize = 0x0614;
+const CRESTS: usize = 0x0615;
+
+/// Start of each member's record: 3 bytes of experience, low byte first, then 8 item
+/// bytes, each an item ID with bit 6 set if equipped.
+const MEMBER_RECORDS: [usize; 3] = [0x0620, 0x0638, 0x0650];
+const ITEMS_OFFSET: usize = 3;
+
+impl GameData {
+    /// Reads the fields a password carries from the work RAM, with encryption key 0.
+    pub(crate) fn from_ram(ram: &Ram) -> Result<Self, String> {
+        let mut data = Self::default();
+        for (index, &code) in ram[NAME..NAME + 4].iter().enumerate() {
+            data.hero_name[index] = *NAME_MOJI_TABLE.get(code as usize).ok_or_else(|| {
+                format!("Invalid name character code {} at ${:04X}", code, NAME + index)
+            })?;
+        }
+        data.prince_flag = ram[PARTY] & 1 != 0;
+        data.princess_flag = data.prince_flag && ram[PARTY] & 2 != 0;
+        data.gold = u16::from_le_bytes([ram[GOLD], ram[GOLD + 1]]);
+
+        if ram[LOCATION] as usize >= LOCATION_TABLE.len() {
+            return Err(format!("Invalid location {} at ${:04X}", ram[LOCATION], LOCATION));
+        }
+        data.location = ram[LOCATION];
+        for (index, flag) in data.progress_flags.iter_mut().enumerate() {
+            *flag = ram[PROGRESS_FLAGS] >> index & 1 != 0;
+        }
+        for (index, crest) in data.crests.iter_mut().enumerate() {
+            *crest = ram[CRESTS] >> index & 1 != 0;
+        }
+
+        for (member, record) in MEMBERS.into_iter().zip(MEMBER_RECORDS) {
+            if !data.is_present(member) {
+                break;
+            }
+            let experience = u32::from_le_bytes([ram[record], ram[record + 1], ram[record + 2], 0]);
+            if experience > 0xF_FFFF {
+                return Err(format!(
+                    "{} experience ({}) at ${:04X} does not fit in 20 bits",
+                    member.name(),
+                    experience,
+                    record
+                ));
+            }
+            let items = &ram[record + ITEMS_OFFSET..record + ITEMS_OFFSET + 8];
+            *data.items_mut(member) = std::array::from_fn(|index| Slot::from_byte(items[index]));
+            match member {
+                Member::Hero => data.hero_experience = experience,
+                Member::Prince => data.prince_experience = experience,
+                Member::Princess => data.princess_experience = experience,
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savestate;
+
+    #[test]
+    fn reads_fixture_save_state() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dq2.fc0");
+        let data = GameData::from_ram(&savestate::read_ram(path).unwrap()).unwrap();
+        let expected = [
+            ("name", "ああああ"),
+            ("location", "ムーンペタ"),
+            ("gold", "1234"),
+            ("hero.experience", "5678"),
+            ("hero.items", "E6,E18,61"),
+            ("prince", "true"),
+            ("prince.experience", "300"),
+            ("prince.items", "2"),
+            ("princess", "true"),
+            ("princess.experience", "70000"),
+            ("princess.items", "E3"),
+            ("flags", "1001100"),
+            ("crests", "10100"),
+        ]
+        .map(|(field, value)| (field.to_string(), value.to_string()));
+        assert_eq!(data, GameData::from_assignments(&expected).unwrap());
+    }
+
+    #[test]
+    fn leaves_out_members_who_have_not_joined() {
+        let mut ram = [0; savestate::RAM_SIZE];
+        ram[PARTY] = 0b10;
+        ram[MEMBER_RECORDS[2]] = 100;
+        let data = GameData::from_ram(&ram).unwrap();
+        assert!(!data.prince_flag && !data.princess_flag);
+        assert_eq!(data.princess_experience, 0);
+    }
+}
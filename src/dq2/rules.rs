@@ -0,0 +1,304 @@
+use super::items::Item;
+use super::{GameData, Member, CREST_TABLE, LOCATION_TABLE, MEMBERS};
+use crate::item::{CatalogueItem, Category};
+use crate::rule::{self, Rule, Severity, Violation};
+
+/// Indices in `LOCATION_TABLE` that can only be reached by ship.
+const SHIP_LOCATIONS: [u8; 4] = [2, 3, 4, 5];
+
+/// Indices of flags in `PROGRESS_FLAG_TABLE`.
+const MOON_FRAGMENT_FLAG: usize = 0;
+const WATERY_GATE_FLAG: usize = 1;
+const MONSTER_FLAG: usize = 3;
+const SHIP_FLAG: usize = 4;
+
+pub(crate) const RULES: [Rule<GameData>; 18] = [
+    Rule { name: "invalid-name", severity: Severity::Error, check: invalid_name },
+    Rule { name: "invalid-location", severity: Severity::Error, check: invalid_location },
+    Rule { name: "experience-cap", severity: Severity::Error, check: experience_cap },
+    Rule {
+        name: "princess-without-prince",
+        severity: Severity::Error,
+        check: princess_without_prince,
+    },
+    Rule { name: "unequippable-item", severity: Severity::Error, check: unequippable_item },
+    Rule { name: "multiple-equipped", severity: Severity::Error, check: multiple_equipped },
+    Rule { name: "unreachable-location", severity: Severity::Error, check: unreachable_location },
+    Rule { name: "ship-before-rescue", severity: Severity::Warning, check: ship_before_rescue },
+    Rule { name: "charm-without-crests", severity: Severity::Error, check: charm_without_crests },
+    Rule { name: "crests-without-ship", severity: Severity::Warning, check: crests_without_ship },
+    Rule {
+        name: "treasure-without-ship",
+        severity: Severity::Warning,
+        check: treasure_without_ship,
+    },
+    Rule { name: "moon-fragment-unheld", severity: Severity::Warning, check: moon_fragment_unheld },
+    Rule { name: "watery-gate-unheld", severity: Severity::Warning, check: watery_gate_unheld },
+    Rule { name: "duplicate-key-item", severity: Severity::Error, check: duplicate_key_item },
+    Rule { name: "duplicate-unique-item", severity: Severity::Error, check: duplicate_unique_item },
+    Rule { name: "item-count", severity: Severity::Error, check: item_count },
+    Rule { name: "absent-member-data", severity: Severity::Warning, check: absent_member_data },
+    Rule { name: "item-gap", severity: Severity::Warning, check: item_gap },
+];
+
+impl GameData {
+    /// Checks the data against the DQ2 rule set.
+    pub(crate) fn validate(&self) -> Vec<Violation> {
+        rule::validate(&RULES, self)
+    }
+}
+
+/// Joins per-member explanations, or returns `None` if there are none.
+fn join(explanations: Vec<String>) -> Option<String> {
+    (!explanations.is_empty()).then(|| explanations.join("; "))
+}
+
+fn invalid_name(data: &GameData) -> Option<String> {
+    data.hero_name.contains(&'\0').then(|| "Invalid character in the hero name".to_string())
+}
+
+fn invalid_location(data: &GameData) -> Option<String> {
+    LOCATION_TABLE
+        .get(data.location as usize)
+        .is_none()
+        .then(|| format!("Invalid location {}", data.location))
+}
+
+fn experience_cap(data: &GameData) -> Option<String> {
+    join(
+        MEMBERS
+            .iter()
+            .filter_map(|&member| {
+                let cap = *member.level_table().last().unwrap();
+                let experience = data.experience(member);
+                (experience > cap).then(|| {
+                    format!("{} has {} experience (at most {})", member.name(), experience, cap)
+                })
+            })
+            .collect(),
+    )
+}
+
+fn princess_without_prince(data: &GameData) -> Option<String> {
+    (data.princess_flag && !data.prince_flag)
+        .then(|| "The princess joins only after the prince".to_string())
+}
+
+fn unequippable_item(data: &GameData) -> Option<String> {
+    join(
+        MEMBERS
+            .iter()
+            .flat_map(|&member| {
+                data.items(member)
+                    .iter()
+                    .filter(move |slot| slot.equipped && !slot.item.can_equip(member))
+                    .map(move |slot| format!("{} cannot equip {}", member.name(), slot.item.name()))
+            })
+            .collect(),
+    )
+}
+
+fn multiple_equipped(data: &GameData) -> Option<String> {
+    let categories = [Category::Weapon, Category::Armor, Category::Shield, Category::Helmet];
+    join(
+        MEMBERS
+            .iter()
+            .flat_map(|&member| {
+                categories.iter().filter_map(move |&category| {
+                    let count = data
+                        .items(member)
+                        .iter()
+                        .filter(|slot| slot.equipped && slot.item.info().category == category)
+                        .count();
+                    (count > 1).then(|| {
+                        format!("{} has {} {} equipped", member.name(), count, category.name())
+                    })
+                })
+            })
+            .collect(),
+    )
+}
+
+fn unreachable_location(data: &GameData) -> Option<String> {
+    let location = LOCATION_TABLE.get(data.location as usize)?;
+    (SHIP_LOCATIONS.contains(&data.location) && !data.progress_flags[SHIP_FLAG])
+        .then(|| format!("{} cannot be reached without the ship", location))
+}
+
+fn ship_before_rescue(data: &GameData) -> Option<String> {
+    // The ship at ルプガナ is lent only after the girl is saved from the monsters.
+    (data.progress_flags[SHIP_FLAG] && !data.progress_flags[MONSTER_FLAG])
+        .then(|| "The ship is held before the monsters at ルプガナ are defeated".to_string())
+}
+
+/// Whether any member of the party holds an item.
+fn holds(data: &GameData, item: Item) -> bool {
+    MEMBERS.iter().any(|&member| data.items(member).iter().any(|slot| slot.item == item))
+}
+
+/// Names the crests the party holds, or lacks if `held` is false.
+fn crests(data: &GameData, held: bool) -> Vec<&'static str> {
+    CREST_TABLE
+        .iter()
+        .zip(data.crests)
+        .filter(|&(_, crest)| crest == held)
+        .map(|(&crest, _)| crest)
+        .collect()
+}
+
+fn charm_without_crests(data: &GameData) -> Option<String> {
+    // ルビスのまもり is only given to a party holding all five crests.
+    let missing = crests(data, false);
+    (holds(data, Item::CharmOfRubiss) && !missing.is_empty()).then(|| {
+        format!("{} held without the crest(s) {}", Item::CharmOfRubiss.name(), missing.join(", "))
+    })
+}
+
+fn crests_without_ship(data: &GameData) -> Option<String> {
+    // Every crest lies across the sea from the continent the party starts on.
+    let held = crests(data, true);
+    (!held.is_empty() && !data.progress_flags[SHIP_FLAG])
+        .then(|| format!("The crest(s) {} held without the ship", held.join(", ")))
+}
+
+fn treasure_without_ship(data: &GameData) -> Option<String> {
+    // ふねのざいほう is fished up from the sea floor with the ship.
+    (holds(data, Item::ShipsTreasure) && !data.progress_flags[SHIP_FLAG])
+        .then(|| format!("{} held without the ship", Item::ShipsTreasure.name()))
+}
+
+fn moon_fragment_unheld(data: &GameData) -> Option<String> {
+    // The fragment is kept after raising the tide, so the party should still hold it.
+    (data.progress_flags[MOON_FRAGMENT_FLAG] && !holds(data, Item::MoonFragment))
+        .then(|| format!("{} used but no longer held", Item::MoonFragment.name()))
+}
+
+fn watery_gate_unheld(data: &GameData) -> Option<String> {
+    (data.progress_flags[WATERY_GATE_FLAG] && !holds(data, Item::WateryGateKey))
+        .then(|| format!("{} used but no longer held", Item::WateryGateKey.name()))
+}
+
+/// Lists the items of a category held more than once, with who holds each copy.
+fn duplicates(data: &GameData, filter: impl Fn(&Item) -> bool) -> Option<String> {
+    let duplicates = Item::ALL
+        .iter()
+        .filter(|&item| filter(item))
+        .filter_map(|&item| {
+            let holders = MEMBERS
+                .iter()
+                .flat_map(|&member| {
+                    let count = data.items(member).iter().filter(|slot| slot.item == item).count();
+                    std::iter::repeat_n(member.name(), count)
+                })
+                .collect::<Vec<_>>();
+            (holders.len() > 1).then(|| format!("{} ({})", item.name(), holders.join(", ")))
+        })
+        .collect::<Vec<_>>();
+    (!duplicates.is_empty()).then(|| duplicates.join(", "))
+}
+
+fn duplicate_key_item(data: &GameData) -> Option<String> {
+    duplicates(data, |item| item.info().unique && item.info().category == Category::KeyItem)
+        .map(|duplicates| format!("Key item(s) held more than once: {}", duplicates))
+}
+
+fn duplicate_unique_item(data: &GameData) -> Option<String> {
+    duplicates(data, |item| item.info().unique && item.info().category != Category::KeyItem)
+        .map(|duplicates| format!("Duplicate unique item(s): {}", duplicates))
+}
+
+fn item_count(data: &GameData) -> Option<String> {
+    // The password stores each member's item count, so it cannot hold the items of a
+    // member who is not in the party, nor count an empty slot as an item.
+    join(
+        MEMBERS
+            .iter()
+            .filter_map(|&member| {
+                let items = data.items(member);
+                let count = super::item_count(items);
+                if count != 0 && !data.is_present(member) {
+                    return Some(format!(
+                        "{} holds {} item(s) but is not in the party",
+                        member.name(),
+                        count
+                    ));
+                }
+                let empty = items[..count]
+                    .iter()
+                    .filter(|slot| slot.item == Item::Nothing && slot.equipped)
+                    .count();
+                (empty != 0).then(|| {
+                    format!("{} counts {} equipped empty slot(s) as items", member.name(), empty)
+                })
+            })
+            .collect(),
+    )
+}
+
+fn absent_member_data(data: &GameData) -> Option<String> {
+    join(
+        [Member::Prince, Member::Princess]
+            .iter()
+            .filter(|&&member| !data.is_present(member))
+            .filter(|&&member| data.experience(member) != 0)
+            .map(|member| format!("{} has experience but is not in the party", member.name()))
+            .collect(),
+    )
+}
+
+fn item_gap(data: &GameData) -> Option<String> {
+    // The game fills item slots in order, so an empty slot is never followed by an item.
+    join(
+        MEMBERS
+            .iter()
+            .filter(|&&member| {
+                let items = data.items(member);
+                items.iter().position(|slot| slot.item == Item::Nothing).is_some_and(|first| {
+                    items[first..].iter().any(|slot| slot.item != Item::Nothing)
+                })
+            })
+            .map(|member| format!("{} holds an item after an empty slot", member.name()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violations(assignments: &[(&str, &str)]) -> Vec<(&'static str, Severity)> {
+        let assignments =
+            assignments.iter().map(|&(f, v)| (f.to_string(), v.to_string())).collect::<Vec<_>>();
+        let data = GameData::from_assignments(&assignments).unwrap();
+        data.validate().iter().map(|violation| (violation.rule, violation.severity)).collect()
+    }
+
+    #[test]
+    fn accepts_reachable_state() {
+        let state = [
+            ("hero.items", "E1,やくそう"),
+            ("prince", "true"),
+            ("princess", "true"),
+            ("flags", "0001100"),
+            ("crests", "11111"),
+            ("location", LOCATION_TABLE[SHIP_LOCATIONS[0] as usize]),
+        ];
+        assert_eq!(violations(&state), []);
+    }
+
+    #[test]
+    fn rates_impossible_states_as_errors_and_unusual_ones_as_warnings() {
+        let unreachable = LOCATION_TABLE[SHIP_LOCATIONS[0] as usize];
+        let cases = [
+            (("princess", "true"), ("princess-without-prince", Severity::Error)),
+            (("hero.items", "E1,E2"), ("multiple-equipped", Severity::Error)),
+            (("location", unreachable), ("unreachable-location", Severity::Error)),
+            (("flags", "0000100"), ("ship-before-rescue", Severity::Warning)),
+            (("crests", "10000"), ("crests-without-ship", Severity::Warning)),
+            (("prince.experience", "100"), ("absent-member-data", Severity::Warning)),
+        ];
+        for (assignment, expected) in cases {
+            assert_eq!(violations(&[assignment]), [expected], "{:?}", assignment);
+        }
+    }
+}
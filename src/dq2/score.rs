@@ -0,0 +1,90 @@
+use super::{GameData, Member};
+use crate::item::Category;
+use crate::score::{self, Progress, MAX_SCORE};
+
+/// Level below which the hero is unlikely to have found unique equipment.
+const UNIQUE_EQUIPMENT_LEVEL: u8 = 15;
+
+/// Scores candidates against each other's names, pairing each with its score.
+pub(crate) fn score_candidates(
+    candidates: Vec<(String, GameData)>,
+) -> Vec<(String, GameData, i32)> {
+    let names = candidates.iter().map(|(_, data)| data.hero_name).collect::<Vec<_>>();
+    candidates
+        .into_iter()
+        .map(|(label, data)| {
+            let score = data.plausibility(&names);
+            (label, data, score)
+        })
+        .collect()
+}
+
+impl GameData {
+    /// Scores how plausible the data is as a real save, out of `MAX_SCORE`.
+    ///
+    /// `names` are the hero names of every candidate being compared, including this one.
+    pub(crate) fn plausibility(&self, names: &[[char; 4]]) -> i32 {
+        let penalty = score::violation_penalty(&self.validate())
+            + score::name_penalty(&self.hero_name)
+            + score::consensus_penalty(&self.hero_name, names)
+            + self.economy_penalty()
+            + self.progress_penalty();
+        (MAX_SCORE - penalty).max(0)
+    }
+
+    /// Returns the progress used to place the data among other saves of the same playthrough.
+    pub(crate) fn progress(&self) -> Progress {
+        Progress {
+            experience: self.hero_experience,
+            milestones: self.progress_flags.iter().chain(&self.crests).copied().collect(),
+            party: vec![self.prince_flag, self.princess_flag],
+        }
+    }
+
+    /// Penalises gold and equipment that the party's experience could not have paid for.
+    fn economy_penalty(&self) -> i32 {
+        let members = self.members();
+        let equipment = members
+            .iter()
+            .flat_map(|&member| self.items(member).iter())
+            .map(|slot| slot.item.info())
+            .filter(|info| {
+                matches!(
+                    info.category,
+                    Category::Weapon | Category::Armor | Category::Shield | Category::Helmet
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut penalty = 0;
+        let experience = members.iter().map(|&member| self.experience(member)).sum::<u32>();
+        let budget = experience + 2000;
+        let spent = equipment.iter().filter_map(|info| info.price).map(u32::from).sum::<u32>();
+        if self.gold as u32 + spent > budget {
+            penalty += 15;
+        }
+        let level = Member::Hero.level(self.hero_experience);
+        if level < UNIQUE_EQUIPMENT_LEVEL && equipment.iter().any(|info| info.unique) {
+            penalty += 15;
+        }
+        penalty
+    }
+
+    /// Penalises progress flags that the party or location make unlikely.
+    fn progress_penalty(&self) -> i32 {
+        let mut penalty = 0;
+        // The prince only sets out after the hero has spoken to his father.
+        if self.prince_flag && !self.progress_flags[5] {
+            penalty += 10;
+        }
+        // The ship is only handed over to a full party.
+        if self.progress_flags[4] && !self.is_present(Member::Princess) {
+            penalty += 10;
+        }
+        // ロンダルキア is the last save point, reached long after the party is complete.
+        if self.location == 5 && !self.is_present(Member::Princess) {
+            penalty += 10;
+        }
+        penalty
+    }
+}
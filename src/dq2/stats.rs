@@ -0,0 +1,122 @@
+use super::{GameData, Member};
+
+/// Experience required to reach each level for the hero, starting from level 1.
+pub(crate) const HERO_LEVEL_TABLE: [u32; 50] = [
+    0, 12, 36, 80, 160, 304, 512, 816, 1216, 1712, 2416, 3296, 4352, 5704, 7312, 9352, 11680,
+    14416, 17632, 21376, 25664, 30880, 37280, 44352, 52256, 61408, 71792, 83440, 96432, 110784,
+    126592, 143872, 162688, 183072, 205120, 228928, 254592, 282112, 311552, 342912, 376320, 411840,
+    449472, 489216, 531136, 575296, 621760, 670592, 721856, 1000000,
+];
+
+/// Experience required to reach each level for the prince, starting from level 1.
+pub(crate) const PRINCE_LEVEL_TABLE: [u32; 45] = [
+    0, 24, 64, 140, 280, 480, 780, 1200, 1700, 2300, 3000, 3900, 5000, 6400, 8000, 10000, 12500,
+    15500, 19000, 23000, 28000, 34000, 41000, 49000, 58000, 68000, 79000, 91000, 104000, 118000,
+    133000, 149000, 166000, 184000, 203000, 223000, 244000, 266000, 289000, 313000, 338000, 364000,
+    391000, 419000, 1000000,
+];
+
+/// Experience required to reach each level for the princess, starting from level 1.
+pub(crate) const PRINCESS_LEVEL_TABLE: [u32; 40] = [
+    0, 30, 80, 170, 330, 560, 890, 1330, 1880, 2560, 3400, 4400, 5600, 7100, 8900, 11000, 13500,
+    16500, 20000, 24000, 28700, 34000, 40000, 47000, 55000, 64000, 74000, 85000, 97000, 110000,
+    124000, 139000, 155000, 172000, 190000, 209000, 229000, 250000, 272000, 1000000,
+];
+
+/// Spells the prince learns, with the level at which they are learned.
+pub(crate) const PRINCE_SPELL_TABLE: [(u8, &str); 10] = [
+    (1, "ホイミ"),
+    (4, "ギラ"),
+    (7, "キアリー"),
+    (10, "ルーラ"),
+    (13, "トヘロス"),
+    (16, "リレミト"),
+    (19, "ベホイミ"),
+    (23, "ザオリク"),
+    (27, "ザラキ"),
+    (31, "メガンテ"),
+];
+
+/// Spells the princess learns, with the level at which they are learned.
+pub(crate) const PRINCESS_SPELL_TABLE: [(u8, &str); 12] = [
+    (1, "スクルト"),
+    (3, "ラリホー"),
+    (6, "マヌーサ"),
+    (9, "バギ"),
+    (12, "キアリク"),
+    (14, "ルカナン"),
+    (17, "トラマナ"),
+    (20, "マホトーン"),
+    (23, "ベホマ"),
+    (26, "イオナズン"),
+    (30, "ザオリク"),
+    (35, "ベホマズン"),
+];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Stats {
+    pub level: u8,
+    pub attack: u8,
+    pub defence: u8,
+    pub spells: Vec<&'static str>,
+}
+
+impl Stats {
+    /// Lists every derived value as a `(field, value)` pair, prefixed with the member name.
+    pub(crate) fn fields(&self, member: Member) -> Vec<(String, String)> {
+        let prefix = member.field_prefix();
+        vec![
+            (format!("{}.level", prefix), self.level.to_string()),
+            (format!("{}.attack", prefix), self.attack.to_string()),
+            (format!("{}.defence", prefix), self.defence.to_string()),
+            (format!("{}.spells", prefix), self.spells.join(",")),
+        ]
+    }
+}
+
+impl Member {
+    pub(crate) fn level_table(self) -> &'static [u32] {
+        match self {
+            Member::Hero => &HERO_LEVEL_TABLE,
+            Member::Prince => &PRINCE_LEVEL_TABLE,
+            Member::Princess => &PRINCESS_LEVEL_TABLE,
+        }
+    }
+
+    pub(crate) fn spell_table(self) -> &'static [(u8, &'static str)] {
+        match self {
+            Member::Hero => &[],
+            Member::Prince => &PRINCE_SPELL_TABLE,
+            Member::Princess => &PRINCESS_SPELL_TABLE,
+        }
+    }
+
+    /// Returns the level the member reaches with the given amount of experience.
+    pub(crate) fn level(self, experience: u32) -> u8 {
+        self.level_table().iter().take_while(|&&threshold| threshold <= experience).count() as u8
+    }
+}
+
+impl GameData {
+    pub(crate) fn stats(&self, member: Member) -> Stats {
+        let level = member.level(self.experience(member));
+
+        let (attack, defence) = self
+            .items(member)
+            .iter()
+            .filter(|slot| slot.equipped)
+            .map(|slot| slot.item.info())
+            .fold((0_u8, 0_u8), |(attack, defence), info| {
+                (attack.saturating_add(info.attack), defence.saturating_add(info.defence))
+            });
+
+        let spells = member
+            .spell_table()
+            .iter()
+            .filter(|&&(spell_level, _)| spell_level <= level)
+            .map(|&(_, spell)| spell)
+            .collect();
+
+        Stats { level, attack, defence, spells }
+    }
+}
@@ -0,0 +1,126 @@
+use super::complete::decrypt_last;
+use super::items::Item;
+use super::{
+    character_codes, checksum, decrypt, encode_game_data, pack, stored_checksum, GameData, Member,
+    Slot, JUMON_MOJI_TABLE, MAX_ENCRYPTION_KEY, MAX_JUMON_LENGTH,
+};
+use crate::complete::{pin_fields, search};
+use crate::rule;
+use crate::vanity::{covers, patterns, FREE_CHARACTERS};
+
+/// Finds up to `limit` valid passwords with the phrase at `position`, keeping the fields
+/// set by `assignments` and with the hero reaching at least `min_level` if given.
+pub(crate) fn vanity(
+    phrase: &str,
+    position: usize,
+    assignments: &[(String, String)],
+    min_level: Option<u8>,
+    limit: usize,
+) -> Result<Vec<(String, GameData)>, String> {
+    let phrase = character_codes(phrase)?;
+    let mut base = GameData::from_assignments(assignments)?;
+    let assigned = |field: &str| assignments.iter().any(|(assigned, _)| assigned == field);
+    if let Some(level) = min_level {
+        let levels = Member::Hero.level_table();
+        let threshold = levels
+            .get((level as usize).wrapping_sub(1))
+            .ok_or_else(|| format!("Invalid level (expected 1 to {})", levels.len()))?;
+        if !assigned("hero.experience") {
+            base.hero_experience = *threshold;
+        }
+    }
+
+    // The password grows with the party and its items, so add them until it reaches
+    // past the phrase
+    let end = position + phrase.len();
+    while encode_game_data(&base).chars().count() < end {
+        if !grow(&mut base, &assigned) {
+            return Err(format!(
+                "The phrase does not fit in a DQ2 password (at most {} characters) at position {}.",
+                MAX_JUMON_LENGTH, position
+            ));
+        }
+    }
+
+    let fixed = |label: &str| assignments.iter().any(|(field, _)| covers(field, label));
+    let expected = base.fields();
+    let keeps_fields = |data: &GameData| {
+        let actual = data.fields();
+        assignments.iter().all(|(field, _)| {
+            let value = |fields: &[(&str, String)]| {
+                fields.iter().find(|(name, _)| name == field).map(|(_, value)| value.clone())
+            };
+            // The checksum is always recomputed
+            field == "checksum" || value(&expected) == value(&actual)
+        })
+    };
+
+    // Each encryption key gives a different base password to place the phrase in
+    let mut found: Vec<(String, GameData)> = vec![];
+    for free_characters in FREE_CHARACTERS {
+        if !found.is_empty() {
+            break;
+        }
+        for key in 0..=MAX_ENCRYPTION_KEY {
+            let base = GameData { encryption_key: key, ..base };
+            let base_codes = character_codes(&encode_game_data(&base))?;
+            let pins = pin_fields(&base.layout(), base_codes.len(), fixed, |bit| {
+                (bit as usize / 6, 5 - bit % 6)
+            });
+            for pattern in patterns(&base_codes, &phrase, position, &pins, free_characters)? {
+                search(&pattern, &pins, decrypt_last, &mut |codes| {
+                    let bytes = pack(&decrypt(codes));
+                    if found.len() >= limit || checksum(&bytes) != stored_checksum(&bytes) {
+                        return;
+                    }
+                    let Ok(data) = GameData::from_bytes(&bytes) else {
+                        return;
+                    };
+                    if rule::has_errors(&data.validate())
+                        || !keeps_fields(&data)
+                        || min_level
+                            .is_some_and(|level| Member::Hero.level(data.hero_experience) < level)
+                    {
+                        return;
+                    }
+                    let password: String =
+                        codes.iter().map(|&code| JUMON_MOJI_TABLE[code as usize]).collect();
+                    if !found.iter().any(|(other, _)| *other == password) {
+                        found.push((password, data));
+                    }
+                });
+                let widened = free_characters != FREE_CHARACTERS[0];
+                if found.len() >= limit || widened && !found.is_empty() {
+                    return Ok(found);
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Lengthens the password by adding a party member, or else an item to the first member
+/// with a free slot, leaving the fields set on the command line alone. Returns `false`
+/// if there is nothing left to add.
+fn grow(data: &mut GameData, assigned: &dyn Fn(&str) -> bool) -> bool {
+    if !data.prince_flag && !assigned("prince") {
+        data.prince_flag = true;
+        return true;
+    }
+    if data.prince_flag && !data.princess_flag && !assigned("princess") {
+        data.princess_flag = true;
+        return true;
+    }
+    for member in data.members() {
+        if assigned(&format!("{}.items", member.field_prefix())) {
+            continue;
+        }
+        // やくそう can be bought in any number, so it looks natural in any inventory
+        if let Some(slot) = data.items_mut(member).iter_mut().find(|slot| **slot == Slot::default())
+        {
+            *slot = Slot { item: Item::Herb, equipped: false };
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,80 @@
+use bitstream_io::{BigEndian, BitRead, BitReader};
+use prettytable::{row, Table};
+
+/// Formats each password character with its code, decrypted value and position in the packed bits.
+///
+/// `offsets` gives the first packed bit of each character, in password order.
+pub(crate) fn tabulate_characters(
+    input: &str,
+    codes: &[u8],
+    decrypted: &[u8],
+    offsets: &[usize],
+) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["Position", "Character", "Code", "Decrypted", "Packed Bits"]);
+    for (index, character) in input.chars().enumerate() {
+        table.add_row(row![
+            index,
+            character,
+            codes[index],
+            format!("{:06b} ({})", decrypted[index], decrypted[index]),
+            format!("{}-{}", offsets[index], offsets[index] + 5)
+        ]);
+    }
+    table.to_string()
+}
+
+/// Formats the packed bytes in hexadecimal and binary.
+pub(crate) fn tabulate_bytes(bytes: &[u8]) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["Byte", "Hex", "Binary"]);
+    for (index, byte) in bytes.iter().enumerate() {
+        table.add_row(row![index, format!("{:02X}", byte), format!("{:08b}", byte)]);
+    }
+    table.to_string()
+}
+
+/// Formats the bits of each field, as laid out by `layout`, read back from the packed bytes.
+pub(crate) fn tabulate_layout(bytes: &[u8], layout: &[(String, u32, u32)]) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["Bits", "Field", "Binary", "Value"]);
+
+    // Fields that do not fit in the bytes are left out, and any remaining bits
+    // are shown as unused, in chunks of at most a byte.
+    let total_bits = bytes.len() as u32 * 8;
+    let mut fields = vec![];
+    let mut used_bits = 0;
+    for (label, bits, _) in layout {
+        if used_bits + bits > total_bits {
+            break;
+        }
+        fields.push((label.as_str(), *bits));
+        used_bits += bits;
+    }
+    while used_bits < total_bits {
+        let bits = (total_bits - used_bits).min(8);
+        fields.push(("(unused)", bits));
+        used_bits += bits;
+    }
+
+    let mut reader = BitReader::endian(bytes, BigEndian);
+    let mut offset = 0;
+    for (label, bits) in fields {
+        let value = reader.read::<u32>(bits).unwrap();
+        table.add_row(row![
+            format!("{}-{}", offset, offset + bits - 1),
+            label,
+            format!("{:0width$b}", value, width = bits as usize),
+            value
+        ]);
+        offset += bits;
+    }
+
+    table.to_string()
+}
+
+/// Formats the stored and computed checksums, noting whether they match.
+pub(crate) fn format_checksum(stored: u16, computed: u16) -> String {
+    let verdict = if stored == computed { "valid" } else { "invalid" };
+    format!("Checksum: stored {}, computed {} ({})", stored, computed, verdict)
+}
@@ -0,0 +1,82 @@
+use crate::Game;
+
+/// Most characters a damaged password may differ from a well-known one to be matched to it.
+const MAX_DISTANCE: usize = 6;
+
+/// A password that is well known among players, with what it is known for.
+pub(crate) struct FamousPassword {
+    pub game: Game,
+    pub password: &'static str,
+    pub description: &'static str,
+    // Where players came across the password
+    pub source: &'static str,
+}
+
+/// Well-known passwords, checked on every decode and recovery, and against the best
+/// result of a merge or completion.
+///
+/// Only passwords that the decoder accepts are listed, so that a match is never a typo.
+pub(crate) const FAMOUS_PASSWORDS: [FamousPassword; 1] = [FamousPassword {
+    game: Game::Dq2,
+    password: "ゆうていみやおうきむこうほりいゆうじとりやまあきらぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺぺ",
+    description: "ゆうていみやおう password spelling the developers' names, which gives the hero もょもと",
+    source: "passed around among players since the game came out in 1987",
+}];
+
+/// Counts the positions at which two passwords of the same length differ.
+fn distance(a: &str, b: &str) -> Option<usize> {
+    (a.chars().count() == b.chars().count())
+        .then(|| a.chars().zip(b.chars()).filter(|(a, b)| a != b).count())
+}
+
+/// Finds the well-known password nearest to the input, with the number of characters
+/// that differ, if any is within `MAX_DISTANCE`.
+pub(crate) fn nearest(game: Game, input: &str) -> Option<(&'static FamousPassword, usize)> {
+    FAMOUS_PASSWORDS
+        .iter()
+        .filter(|famous| famous.game == game)
+        .filter_map(|famous| distance(famous.password, input).map(|distance| (famous, distance)))
+        .filter(|&(_, distance)| distance <= MAX_DISTANCE)
+        .min_by_key(|&(_, distance)| distance)
+}
+
+/// Describes the well-known password the input is or is nearest to, if any.
+pub(crate) fn describe(game: Game, input: &str) -> Option<String> {
+    nearest(game, input).map(|(famous, distance)| match distance {
+        0 => format!("This is the well-known {}, {}.", famous.description, famous.source),
+        _ => format!(
+            "This is {} character(s) away from the well-known {}, {}:\n{}",
+            distance, famous.description, famous.source, famous.password
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dq1, dq2};
+
+    #[test]
+    fn famous_passwords_decode() {
+        for famous in &FAMOUS_PASSWORDS {
+            let decoded = match famous.game {
+                Game::Dq1 => dq1::decode_jumon(famous.password).map(|_| ()),
+                Game::Dq2 => dq2::decode_jumon(famous.password).map(|_| ()),
+            };
+            assert_eq!(decoded, Ok(()), "{}", famous.password);
+        }
+    }
+
+    #[test]
+    fn finds_famous_password_from_damaged_copy() {
+        for famous in &FAMOUS_PASSWORDS {
+            let mut damaged = famous.password.chars().collect::<Vec<_>>();
+            damaged[1] = if damaged[1] == 'あ' { 'い' } else { 'あ' };
+            damaged[7] = if damaged[7] == 'あ' { 'い' } else { 'あ' };
+            let damaged = damaged.into_iter().collect::<String>();
+            let (found, distance) = nearest(famous.game, &damaged).unwrap();
+            assert_eq!((found.password, distance), (famous.password, 2));
+            assert!(nearest(famous.game, famous.password).is_some_and(|(_, d)| d == 0));
+        }
+    }
+}
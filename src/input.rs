@@ -0,0 +1,140 @@
+use colored::Colorize;
+
+pub(crate) fn split_dakuten(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            'が' => result.push_str("か゛"),
+            'ぎ' => result.push_str("き゛"),
+            'ぐ' => result.push_str("く゛"),
+            'げ' => result.push_str("け゛"),
+            'ご' => result.push_str("こ゛"),
+            'ざ' => result.push_str("さ゛"),
+            'じ' => result.push_str("し゛"),
+            'ず' => result.push_str("す゛"),
+            'ぜ' => result.push_str("せ゛"),
+            'ぞ' => result.push_str("そ゛"),
+            'だ' => result.push_str("た゛"),
+            'ぢ' => result.push_str("ち゛"),
+            'づ' => result.push_str("つ゛"),
+            'で' => result.push_str("て゛"),
+            'ど' => result.push_str("と゛"),
+            'ば' => result.push_str("は゛"),
+            'び' => result.push_str("ひ゛"),
+            'ぶ' => result.push_str("ふ゛"),
+            'べ' => result.push_str("へ゛"),
+            'ぼ' => result.push_str("ほ゛"),
+            'ぱ' => result.push_str("は゜"),
+            'ぴ' => result.push_str("ひ゜"),
+            'ぷ' => result.push_str("ふ゜"),
+            'ぺ' => result.push_str("へ゜"),
+            'ぽ' => result.push_str("ほ゜"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Joins password arguments, ignoring any spacing within the password.
+pub(crate) fn join_password(args: &[String]) -> String {
+    args.join("").split_whitespace().collect::<String>()
+}
+
+/// Parses a player name, splitting dakuten into separate characters and
+/// padding it to 4 characters with spaces as the games do.
+pub(crate) fn parse_name(input: &str) -> Result<[char; 4], String> {
+    let chars = split_dakuten(input).chars().collect::<Vec<char>>();
+    if chars.is_empty() || chars.len() > 4 {
+        return Err(format!("Invalid name (1 to 4 characters after splitting dakuten): {}", input));
+    }
+
+    let mut name = ['　'; 4];
+    name[..chars.len()].copy_from_slice(&chars);
+    Ok(name)
+}
+
+/// Parses a name and confirms that every character exists in the game's name table.
+pub(crate) fn parse_game_name(input: &str, table: &[char; 64]) -> Result<[char; 4], String> {
+    let name = parse_name(input)?;
+    if let Some(c) = name.iter().find(|c| !table.contains(c)) {
+        return Err(format!("Unsupported name character: {}", c));
+    }
+    Ok(name)
+}
+
+/// Parses a string of binary digits (e.g. `10111`) into flags, first digit first.
+pub(crate) fn parse_flags(input: &str) -> Result<Vec<bool>, String> {
+    if input.is_empty() || !input.chars().all(|c| c == '0' || c == '1') {
+        return Err(format!("Invalid flags (expected binary digits such as 10111): {}", input));
+    }
+    Ok(input.chars().map(|c| c == '1').collect())
+}
+
+/// Parses a string of binary digits into exactly `N` flags.
+pub(crate) fn parse_flag_array<const N: usize>(input: &str) -> Result<[bool; N], String> {
+    let flags = parse_flags(input)?;
+    flags.try_into().map_err(|_| format!("Expected {} binary digits: {}", N, input))
+}
+
+/// Formats flags as a string of binary digits, first flag first.
+pub(crate) fn format_flags(flags: &[bool]) -> String {
+    flags.iter().map(|&flag| if flag { '1' } else { '0' }).collect()
+}
+
+/// Colours the characters of each password in a label that differ from the input red.
+///
+/// A label holds one password per line, optionally followed by a space and a note.
+pub(crate) fn highlight_substitutions(label: &str, input: &str) -> String {
+    label
+        .lines()
+        .map(|line| {
+            let (password, note) = line.split_at(line.find(' ').unwrap_or(line.len()));
+            let mut formatted = String::with_capacity(line.len());
+            for (index, character) in password.chars().enumerate() {
+                if input.chars().nth(index) == Some(character) {
+                    formatted.push(character);
+                } else {
+                    formatted = format!("{}{}", formatted, character.to_string().red());
+                }
+            }
+            formatted + note
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses an unsigned integer no larger than `max`.
+pub(crate) fn parse_number(input: &str, max: u32) -> Result<u32, String> {
+    match input.parse::<u32>() {
+        Ok(value) if value <= max => Ok(value),
+        _ => Err(format!("Invalid value (expected 0 to {}): {}", max, input)),
+    }
+}
+
+/// Parses a boolean written as `0`/`1` or `false`/`true`.
+pub(crate) fn parse_bool(input: &str) -> Result<bool, String> {
+    match input {
+        "0" | "false" => Ok(false),
+        "1" | "true" => Ok(true),
+        _ => Err(format!("Invalid boolean (expected 0, 1, false or true): {}", input)),
+    }
+}
+
+/// Splits a `field=value` assignment.
+pub(crate) fn parse_assignment(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((field, value)) => Ok((field.trim().to_string(), value.trim().to_string())),
+        None => Err(format!("Invalid assignment (expected field=value): {}", input)),
+    }
+}
+
+/// Parses the lines of a state file into `field=value` assignments,
+/// skipping blank lines and `#` comments.
+pub(crate) fn parse_state(input: &str) -> Result<Vec<(String, String)>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_assignment)
+        .collect()
+}
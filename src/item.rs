@@ -0,0 +1,62 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Category {
+    Nothing,
+    Weapon,
+    Armor,
+    Shield,
+    Helmet,
+    KeyItem,
+    Consumable,
+    Invalid,
+}
+
+impl Category {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Category::Nothing => "-",
+            Category::Weapon => "Weapon",
+            Category::Armor => "Armor",
+            Category::Shield => "Shield",
+            Category::Helmet => "Helmet",
+            Category::KeyItem => "Key Item",
+            Category::Consumable => "Consumable",
+            Category::Invalid => "Invalid",
+        }
+    }
+}
+
+/// Formats an optional price for display.
+pub(crate) fn format_price(price: Option<u16>) -> String {
+    match price {
+        Some(price) => price.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// An item type whose variants are listed in catalogue order, by ID.
+pub(crate) trait CatalogueItem: Copy + PartialEq + 'static {
+    /// Every variant, indexed by ID.
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+
+    fn id(self) -> u8 {
+        Self::ALL.iter().position(|&item| item == self).unwrap() as u8
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    /// Parses an item from either its name or its ID.
+    fn parse(input: &str) -> Result<Self, String> {
+        if let Some(&item) = Self::ALL.iter().find(|item| item.name() == input) {
+            return Ok(item);
+        }
+        input
+            .parse::<u8>()
+            .ok()
+            .and_then(Self::from_id)
+            .ok_or_else(|| format!("Unknown item: {}", input))
+    }
+}
@@ -0,0 +1,38 @@
+use crate::input;
+
+/// Groups of kana that are easily mistaken for each other when copied by hand or read aloud.
+pub(crate) const CONFUSION_TABLE: [&str; 17] = [
+    "ぬめ",
+    "ねれわ",
+    "るろ",
+    "さちき",
+    "はほ",
+    "いり",
+    "あお",
+    "うら",
+    "くへ",
+    "しつ",
+    "ばぱ",
+    "びぴ",
+    "ぶぷ",
+    "べぺ",
+    "ぼぽ",
+    "じぢ",
+    "ずづ",
+];
+
+pub(crate) fn is_confusable(c: char) -> bool {
+    CONFUSION_TABLE.iter().any(|group| group.contains(c))
+}
+
+pub(crate) fn has_dakuten(c: char) -> bool {
+    input::split_dakuten(&c.to_string()).chars().count() > 1
+}
+
+/// Rates how hard a password is to copy by hand, as the number of confusable kana
+/// followed by the number of kana with dakuten, so that lower is easier.
+pub(crate) fn copy_difficulty(password: &str) -> (usize, usize) {
+    let confusable = password.chars().filter(|&c| is_confusable(c)).count();
+    let dakuten = password.chars().filter(|&c| has_dakuten(c)).count();
+    (confusable, dakuten)
+}
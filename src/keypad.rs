@@ -0,0 +1,255 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+use prettytable::{row, Table};
+
+/// Label of the key that ends password entry.
+pub(crate) const END_KEY: &str = "おわり";
+
+/// The cell the cursor starts on, at the top left of the grid.
+pub(crate) const START: (usize, usize) = (0, 0);
+
+/// The kana grid of a password screen.
+pub(crate) struct Grid {
+    // One string per row, with `　` for an empty cell
+    pub rows: &'static [&'static str],
+    // The cell of the key that ends entry
+    pub end: (usize, usize),
+    // Whether the cursor wraps around from one edge to the opposite one
+    pub wrap: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+const DIRECTIONS: [Direction; 4] =
+    [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Up => "↑",
+            Direction::Down => "↓",
+            Direction::Left => "←",
+            Direction::Right => "→",
+        })
+    }
+}
+
+/// The cursor moves to a key, followed by a press of A to enter it.
+pub(crate) struct Step {
+    pub key: String,
+    pub moves: Vec<Direction>,
+}
+
+impl Grid {
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn width(&self) -> usize {
+        self.rows.iter().map(|row| row.chars().count()).max().unwrap_or(0)
+    }
+
+    fn is_key(&self, (row, column): (usize, usize)) -> bool {
+        (row, column) == self.end
+            || self.rows[row].chars().nth(column).is_some_and(|cell| cell != '　')
+    }
+
+    /// Finds the cell holding a password character.
+    fn locate(&self, c: char) -> Option<(usize, usize)> {
+        self.rows.iter().enumerate().find_map(|(row, cells)| {
+            cells.chars().position(|cell| cell == c).map(|column| (row, column))
+        })
+    }
+
+    /// Moves the cursor one press in a direction, skipping over empty cells.
+    ///
+    /// Without wrapping, the cursor stays put at the edge of the grid.
+    pub(crate) fn step(&self, from: (usize, usize), direction: Direction) -> (usize, usize) {
+        let (height, width) = (self.height() as isize, self.width() as isize);
+        let (dr, dc) = match direction {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        };
+        let (mut row, mut column) = (from.0 as isize, from.1 as isize);
+        loop {
+            row += dr;
+            column += dc;
+            if !(0..height).contains(&row) || !(0..width).contains(&column) {
+                if !self.wrap {
+                    return from;
+                }
+                row = row.rem_euclid(height);
+                column = column.rem_euclid(width);
+            }
+            let cell = (row as usize, column as usize);
+            if cell == from || self.is_key(cell) {
+                return cell;
+            }
+        }
+    }
+
+    /// Finds a shortest path from one cell to another, with the fewest changes of
+    /// direction among shortest paths so that the directions are easy to follow.
+    fn path(&self, from: (usize, usize), to: (usize, usize)) -> Result<Vec<Direction>, String> {
+        // Dijkstra's algorithm over the cursor and the direction of its last move,
+        // costing moves first and turns second
+        type State = ((usize, usize), Option<Direction>);
+        let start: State = (from, None);
+        let mut costs = HashMap::from([(start, (0, 0))]);
+        let mut previous: HashMap<State, (State, Direction)> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse(((0, 0), start))]);
+        while let Some(Reverse((cost, state @ (cell, last)))) = queue.pop() {
+            if costs.get(&state).is_some_and(|&best| best < cost) {
+                continue;
+            }
+            if cell == to {
+                let mut moves = vec![];
+                let mut state = state;
+                while let Some(&(before, direction)) = previous.get(&state) {
+                    moves.push(direction);
+                    state = before;
+                }
+                moves.reverse();
+                return Ok(moves);
+            }
+            for direction in DIRECTIONS {
+                let next = (self.step(cell, direction), Some(direction));
+                let turned = last.is_some_and(|last| last != direction) as usize;
+                let next_cost = (cost.0 + 1, cost.1 + turned);
+                if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                    costs.insert(next, next_cost);
+                    previous.insert(next, (state, direction));
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+        Err("A key of the password screen cannot be reached.".to_string())
+    }
+}
+
+/// Plans the shortest controller input to enter a password, starting with the cursor
+/// on the top left key and ending with a press of the end key.
+pub(crate) fn plan(grid: &Grid, password: &str) -> Result<Vec<Step>, String> {
+    let mut cursor = START;
+    let mut steps = vec![];
+    for c in password.chars() {
+        let cell = grid.locate(c).ok_or_else(|| format!("Unsupported input character: {}", c))?;
+        steps.push(Step { key: c.to_string(), moves: grid.path(cursor, cell)? });
+        cursor = cell;
+    }
+    steps.push(Step { key: END_KEY.to_string(), moves: grid.path(cursor, grid.end)? });
+    Ok(steps)
+}
+
+/// Counts the button presses of a plan, including the presses of A.
+pub(crate) fn press_count(steps: &[Step]) -> usize {
+    steps.iter().map(|step| step.moves.len() + 1).sum()
+}
+
+/// Formats a plan as a table of directions, one row per key.
+pub(crate) fn tabulate_steps(steps: &[Step]) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["#", "Key", "Directions", "Presses"]);
+    for (index, step) in steps.iter().enumerate() {
+        let mut directions = vec![];
+        for run in step.moves.chunk_by(|a, b| a == b) {
+            directions.push(match run.len() {
+                1 => run[0].to_string(),
+                count => format!("{}×{}", run[0], count),
+            });
+        }
+        directions.push("A".to_string());
+        table.add_row(row![index + 1, step.key, directions.join(" "), step.moves.len() + 1]);
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dq1;
+
+    const SMALL: Grid = Grid { rows: &["あいう", "か　く"], end: (1, 1), wrap: false };
+
+    fn arrows(moves: &[Direction]) -> String {
+        moves.iter().map(Direction::to_string).collect()
+    }
+
+    /// Counts the fewest presses from one cell to another, trying every move.
+    fn distance(grid: &Grid, from: (usize, usize), to: (usize, usize)) -> usize {
+        let mut reached = vec![from];
+        for presses in 0.. {
+            if reached.contains(&to) {
+                return presses;
+            }
+            reached = reached
+                .iter()
+                .flat_map(|&cell| DIRECTIONS.map(|direction| grid.step(cell, direction)))
+                .collect();
+            reached.sort();
+            reached.dedup();
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn steps_over_empty_cells_and_wraps_at_the_edges() {
+        let grid = &dq1::PASSWORD_GRID;
+        assert_eq!(grid.step((2, 5), Direction::Right), (2, 7));
+        assert_eq!(grid.step(START, Direction::Left), (0, 14));
+        assert_eq!(grid.step(START, Direction::Up), (4, 0));
+        // Without wrapping, the cursor stays at the edge
+        assert_eq!(SMALL.step(START, Direction::Left), START);
+        assert_eq!(SMALL.step((0, 1), Direction::Down), (1, 1));
+    }
+
+    #[test]
+    fn plans_shortest_moves_ending_on_each_key() {
+        let grid = &dq1::PASSWORD_GRID;
+        let password = "あごわぼゆ";
+        let steps = plan(grid, password).unwrap();
+        assert_eq!(steps.len(), password.chars().count() + 1);
+        assert_eq!(steps.last().unwrap().key, END_KEY);
+
+        let mut cursor = START;
+        for step in &steps {
+            let target = match step.key.as_str() {
+                END_KEY => grid.end,
+                key => grid.locate(key.chars().next().unwrap()).unwrap(),
+            };
+            assert_eq!(step.moves.len(), distance(grid, cursor, target), "{}", step.key);
+            for &direction in &step.moves {
+                cursor = grid.step(cursor, direction);
+            }
+            assert_eq!(cursor, target, "{}", step.key);
+        }
+        assert_eq!(steps[0].moves.len(), 0);
+        assert_eq!(
+            press_count(&steps),
+            steps.iter().map(|step| step.moves.len()).sum::<usize>() + 6
+        );
+    }
+
+    #[test]
+    fn prefers_paths_with_fewer_turns() {
+        let grid =
+            Grid { rows: &["あいう", "かきく", "さしす"], end: (2, 2), wrap: false };
+        let path = arrows(&grid.path(START, (2, 2)).unwrap());
+        assert!(path == "→→↓↓" || path == "↓↓→→", "{}", path);
+    }
+
+    #[test]
+    fn rejects_characters_missing_from_the_grid() {
+        assert!(plan(&SMALL, "あえ").is_err());
+    }
+}
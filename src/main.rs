@@ -1,61 +1,438 @@
+mod complete;
+mod diff;
 mod dq1;
 mod dq2;
-use std::env;
+mod explain;
+mod famous;
+mod input;
+mod item;
+mod kana;
+mod keypad;
+mod merge;
+mod movie;
+mod notation;
+mod ocr;
+mod optimise;
+mod render;
+mod rule;
+mod savestate;
+mod score;
+mod sensitivity;
+mod vanity;
 
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::fs;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use prettytable::{row, Table};
 
-fn split_dakuten(input: &str) -> String {
-    let mut result = String::with_capacity(input.len());
-    for c in input.chars() {
-        match c {
-            'が' => result.push_str("か゛"),
-            'ぎ' => result.push_str("き゛"),
-            'ぐ' => result.push_str("く゛"),
-            'げ' => result.push_str("け゛"),
-            'ご' => result.push_str("こ゛"),
-            'ざ' => result.push_str("さ゛"),
-            'じ' => result.push_str("し゛"),
-            'ず' => result.push_str("す゛"),
-            'ぜ' => result.push_str("せ゛"),
-            'ぞ' => result.push_str("そ゛"),
-            'だ' => result.push_str("た゛"),
-            'ぢ' => result.push_str("ち゛"),
-            'づ' => result.push_str("つ゛"),
-            'で' => result.push_str("て゛"),
-            'ど' => result.push_str("と゛"),
-            'ば' => result.push_str("は゛"),
-            'び' => result.push_str("ひ゛"),
-            'ぶ' => result.push_str("ふ゛"),
-            'べ' => result.push_str("へ゛"),
-            'ぼ' => result.push_str("ほ゛"),
-            'ぱ' => result.push_str("は゜"),
-            'ぴ' => result.push_str("ひ゜"),
-            'ぷ' => result.push_str("ふ゜"),
-            'ぺ' => result.push_str("へ゜"),
-            'ぽ' => result.push_str("ほ゜"),
-            _ => result.push(c),
-        }
+#[derive(Parser)]
+#[command(version, about = "ドラゴンクエストⅠ・Ⅱ　ふっかつのじゅもん　修正ツール")]
+struct Cli {
+    /// Game the password belongs to (detected from the password if omitted)
+    #[arg(short, long, global = true, value_enum)]
+    game: Option<Game>,
+
+    /// How passwords are written on the command line (formats other than kana need --game)
+    #[arg(long, global = true, value_enum, default_value_t = notation::Format::Kana)]
+    input_format: notation::Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Game {
+    Dq1,
+    Dq2,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check whether a password is valid
+    Check(PasswordArgs),
+    /// Recover a password containing an error in one character
+    Recover(RecoverArgs),
+    /// Decode a valid password and display its game data
+    Decode(DecodeArgs),
+    /// Encode game data into a valid password
+    Encode(EncodeArgs),
+    /// Edit fields of a valid password and encode the result
+    Edit(EditArgs),
+    /// Show which fields of the game data differ between two valid passwords
+    Diff(DiffArgs),
+    /// Recompute the checksum of a password, keeping its data
+    Repair(PasswordArgs),
+    /// Convert a password between kana, character indices and packed bytes, without validation
+    Convert(ConvertArgs),
+    /// Resolve a password from two copies that are wrong in different places
+    Merge(MergeArgs),
+    /// Search for the valid passwords that complete a partial password
+    Complete(CompleteArgs),
+    /// Read a password from a screenshot of the password screen, then check or complete it
+    Screenshot(ScreenshotArgs),
+    /// Find the valid password that maximises fields, keeping the fields that are set
+    Optimise(OptimiseArgs),
+    /// Search for valid passwords that contain a phrase
+    Vanity(VanityArgs),
+    /// Show which fields would change if each character of a password were wrong
+    Sensitivity(PasswordArgs),
+    /// Plan the fewest controller presses to enter a password on the password screen
+    Plan(PasswordArgs),
+    /// Write an FCEUX movie (.fm2) that enters a password on the password screen
+    Movie(MovieArgs),
+    /// Read the game data from an NES RAM dump or FCEUX save state and encode its password
+    Import(ImportArgs),
+    /// Draw a password as an image (.svg or .png) in the style of the game's password window
+    Render(RenderArgs),
+    /// List every password for the same game data, one per encryption key
+    Variants(SourceArgs),
+    /// Pick the equivalent password that is easiest to copy by hand
+    Easiest(SourceArgs),
+    /// List the items of the game catalogue
+    Catalogue,
+}
+
+#[derive(Args)]
+struct PasswordArgs {
+    /// Password (spacing within the password is ignored)
+    #[arg(required = true, num_args = 1..)]
+    password: Vec<String>,
+}
+
+/// Progress flags given on the command line, first flag first.
+type Flags = Vec<bool>;
+
+#[derive(Args)]
+struct RecoverArgs {
+    /// Name to keep fixed in recovered passwords
+    #[arg(short, long, value_parser = input::parse_name)]
+    name: Option<[char; 4]>,
+
+    /// Progress flags to keep fixed, as 5 (DQ1) or 7 (DQ2) binary digits (e.g. 10111)
+    #[arg(short, long, value_parser = parse_flags_arg)]
+    flags: Option<Flags>,
+
+    /// Keep the checksum fixed in recovered passwords
+    #[arg(short, long)]
+    keep_checksum: bool,
+
+    /// File of other passwords from the same playthrough, one per line in play order
+    #[arg(short, long, value_name = "FILE")]
+    reference: Option<String>,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+}
+
+#[derive(Args)]
+struct CompleteArgs {
+    /// Name of the completed passwords
+    #[arg(short, long, value_parser = input::parse_name)]
+    name: Option<[char; 4]>,
+
+    /// Progress flags of the completed passwords, as 5 (DQ1) or 7 (DQ2) binary digits
+    #[arg(short, long, value_parser = parse_flags_arg)]
+    flags: Option<Flags>,
+
+    /// Item held in the completed passwords, by name or ID (may be repeated)
+    #[arg(short, long = "item", value_name = "ITEM")]
+    items: Vec<String>,
+
+    /// Length of the full password (required for DQ2, whose length depends on the items)
+    #[arg(short, long)]
+    length: Option<usize>,
+
+    /// Number of completions to show, most plausible first
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+
+    /// Known characters of the password, with `?` for each unknown character;
+    /// any missing characters at the end are unknown
+    #[arg(required = true, num_args = 1..)]
+    password: Vec<String>,
+}
+
+#[derive(Args)]
+struct ScreenshotArgs {
+    /// Font sheet of the game's kana as 8×8 tiles, in the order あ…わ followed by ゛ and ゜,
+    /// to read with instead of the built-in font
+    #[arg(long, value_name = "FILE")]
+    font: Option<String>,
+
+    /// Region of the screen holding the password, as x,y,width,height in screen pixels
+    #[arg(long, value_name = "X,Y,W,H", value_parser = parse_region)]
+    crop: Option<(usize, usize, usize, usize)>,
+
+    /// Times the screenshot is scaled up from the NES screen, as one factor or as
+    /// ACROSSxDOWN (such as 2.29x2 for an 8:7 capture); worked out from the image if omitted
+    #[arg(long, value_name = "FACTOR", value_parser = parse_scale)]
+    scale: Option<(f64, f64)>,
+
+    /// PNG screenshot of the password screen
+    image: String,
+}
+
+#[derive(Args)]
+struct OptimiseArgs {
+    /// State file of `field=value` lines to keep, as printed by `decode --state`
+    #[arg(long, value_name = "FILE")]
+    state: Option<String>,
+
+    /// Set a field of the game data to keep (may be repeated)
+    #[arg(long = "set", value_name = "FIELD=VALUE", value_parser = input::parse_assignment)]
+    assignments: Vec<(String, String)>,
+
+    /// Numeric field to maximise (may be repeated; earlier fields take priority)
+    #[arg(long = "maximise", value_name = "FIELD", required = true)]
+    fields: Vec<String>,
+}
+
+#[derive(Args)]
+struct VanityArgs {
+    /// Position of the first character of the phrase, counting from 0
+    #[arg(short, long, default_value_t = 0)]
+    position: usize,
+
+    /// Set a field of the game data to keep (may be repeated)
+    #[arg(long = "set", value_name = "FIELD=VALUE", value_parser = input::parse_assignment)]
+    assignments: Vec<(String, String)>,
+
+    /// Lowest level of the hero
+    #[arg(long)]
+    min_level: Option<u8>,
+
+    /// Number of passwords to show, most plausible first
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+
+    /// Phrase of password characters (spacing within the phrase is ignored)
+    #[arg(required = true, num_args = 1..)]
+    phrase: Vec<String>,
+}
+
+#[derive(Args)]
+struct DecodeArgs {
+    /// Print the game data as `field=value` lines instead of a table
+    #[arg(short, long)]
+    state: bool,
+
+    /// Explain the decoding bit by bit, even if the checksum is invalid
+    #[arg(short, long, conflicts_with = "state")]
+    explain: bool,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+}
+
+#[derive(Args)]
+struct EncodeArgs {
+    /// State file of `field=value` lines, as printed by `decode --state`
+    #[arg(long, value_name = "FILE")]
+    state: Option<String>,
+
+    /// Set a field of the game data (may be repeated)
+    #[arg(long = "set", value_name = "FIELD=VALUE", value_parser = input::parse_assignment)]
+    assignments: Vec<(String, String)>,
+}
+
+#[derive(Args)]
+struct EditArgs {
+    /// Set a field of the game data (may be repeated)
+    #[arg(long = "set", value_name = "FIELD=VALUE", value_parser = input::parse_assignment)]
+    assignments: Vec<(String, String)>,
+
+    /// Keep the stored checksum instead of recomputing it
+    #[arg(short, long)]
+    keep_checksum: bool,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Original password
+    a: String,
+    /// Modified password
+    b: String,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    /// How to write the password
+    #[arg(short, long, value_enum, default_value_t = notation::Format::Kana)]
+    output_format: notation::Format,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// Raw dump of the 2 KB work RAM, or FCEUX save state (.fc0 to .fc9)
+    file: String,
+}
+
+#[derive(Args)]
+struct MovieArgs {
+    /// File to write the movie to
+    #[arg(short, long, value_name = "FILE")]
+    output: String,
+
+    /// File name of the ROM, recorded in the movie
+    #[arg(long, default_value = "rejumon.nes")]
+    rom: String,
+
+    /// Frames to wait before the first press
+    #[arg(long, default_value_t = 0)]
+    lead: usize,
+
+    /// Frames to hold each button down
+    #[arg(long, default_value_t = 4)]
+    hold: usize,
+
+    /// Frames to release each button before the next press
+    #[arg(long, default_value_t = 4)]
+    release: usize,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// File to write the image to, as SVG or PNG by its extension
+    #[arg(short, long, value_name = "FILE")]
+    output: String,
+
+    /// Font sheet to draw images with instead of the built-in font, as read by the
+    /// screenshot command
+    #[arg(long, value_name = "FILE")]
+    font: Option<String>,
+
+    /// Times to scale up PNG images
+    #[arg(long, default_value_t = 2)]
+    scale: usize,
+
+    /// Write the name, level and gold of the password underneath
+    #[arg(short, long)]
+    summary: bool,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+}
+
+#[derive(Args)]
+struct MergeArgs {
+    /// First copy of the password
+    a: String,
+    /// Second copy of the password
+    b: String,
+}
+
+#[derive(Args)]
+struct SourceArgs {
+    /// State file of `field=value` lines, as printed by `decode --state`
+    #[arg(long, value_name = "FILE", conflicts_with = "password")]
+    state: Option<String>,
+
+    /// Password (spacing within the password is ignored)
+    #[arg(required_unless_present = "state")]
+    password: Vec<String>,
+}
+
+/// Wraps `input::parse_flags` to restrict flags to the lengths used by either game.
+fn parse_flags_arg(input: &str) -> Result<Flags, String> {
+    let flags = input::parse_flags(input)?;
+    if flags.len() != dq1::PROGRESS_FLAG_TABLE.len()
+        && flags.len() != dq2::PROGRESS_FLAG_TABLE.len()
+    {
+        return Err(format!(
+            "Expected {} (DQ1) or {} (DQ2) binary digits: {}",
+            dq1::PROGRESS_FLAG_TABLE.len(),
+            dq2::PROGRESS_FLAG_TABLE.len(),
+            input
+        ));
+    }
+    Ok(flags)
+}
+
+/// Parses a region as `x,y,width,height`.
+fn parse_region(input: &str) -> Result<(usize, usize, usize, usize), String> {
+    let values = input
+        .split(',')
+        .map(|value| value.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("Invalid region (expected x,y,width,height): {}", input))?;
+    match values[..] {
+        [x, y, width, height] => Ok((x, y, width, height)),
+        _ => Err(format!("Invalid region (expected x,y,width,height): {}", input)),
     }
-    result
+}
+
+/// Parses a scale factor, or separate factors across and down as `ACROSSxDOWN`.
+fn parse_scale(input: &str) -> Result<(f64, f64), String> {
+    let factor = |value: &str| value.trim().parse::<f64>().ok().filter(|&factor| factor >= 1.0);
+    let scale = match input.split_once('x') {
+        Some((across, down)) => factor(across).zip(factor(down)),
+        None => factor(input).map(|factor| (factor, factor)),
+    };
+    scale
+        .ok_or_else(|| format!("Invalid scale (expected a factor of 1 or more, or AxD): {}", input))
 }
 
 fn process_dq1(
     input: &str,
-    name: Option<String>,
-    progress_flags: Option<u8>,
+    name: Option<[char; 4]>,
+    progress_flags: Option<&[bool]>,
     keep_checksum: bool,
+    references: &[String],
 ) -> Result<String, String> {
     if let Ok(result) = dq1::decode_jumon(input) {
         let data = dq1::GameData::from_bytes(result.as_slice());
         return Ok(format!(
             "The password is already valid:\n\n{}",
-            dq1::tabulate_game_data(vec![(input.to_string(), data)], input)
+            dq1::tabulate_game_data(dq1::score_candidates(vec![(input.to_string(), data)]), input)
         ));
     }
 
+    // Other saves from the playthrough, whose hero name the substitutions must share
+    let references = references
+        .iter()
+        .map(|password| {
+            decode_dq1(password)
+                .map_err(|err| format!("Invalid reference password {}: {}", password, err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let name = match references.first() {
+        Some(first) if references.iter().any(|reference| reference.name != first.name) => {
+            return Err("The reference passwords have different names.".to_string());
+        }
+        Some(first) if name.is_some_and(|name| name != first.name) => {
+            return Err("The name differs from the reference passwords.".to_string());
+        }
+        Some(first) => Some(first.name),
+        None => name,
+    };
+    let timeline = references.iter().map(|reference| reference.progress()).collect::<Vec<_>>();
+    let fit = |data: &dq1::GameData| {
+        (!timeline.is_empty()).then(|| score::timeline_fit(&data.progress(), &timeline))
+    };
+
+    // The input as it stands, to explain which fields each substitution alters
+    let original = dq1::decode_unchecked(input).ok();
+    if keep_checksum && original.is_none() {
+        return Err(
+            "The checksum cannot be kept, since the password cannot be decoded.".to_string()
+        );
+    }
+
     let mut substitutions: Vec<(String, dq1::GameData)> = Vec::new();
-    let max_index = if keep_checksum { input.len() - 1 } else { input.len() };
-    for input_index_to_replace in 0..max_index {
+    for input_index_to_replace in 0..input.chars().count() {
         for moji in &dq1::JUMON_MOJI_TABLE {
             let mut new_string = String::with_capacity(input.len());
             for (input_index, input_character) in input.chars().enumerate() {
@@ -68,39 +445,57 @@ fn process_dq1(
             if let Ok(decoded) = dq1::decode_jumon(&new_string) {
                 let data = dq1::GameData::from_bytes(decoded.as_slice());
                 // If the player name is known, ignore any substitutions where it is wrong.
-                if let Some(ref player_name) = name {
-                    let player_name_chars = player_name.chars().collect::<Vec<char>>();
-                    if player_name_chars != data.name {
+                if let Some(player_name) = name {
+                    if player_name != data.name {
                         continue;
                     }
                 }
                 // If progress flags are specified, ignore any substitutions where they change.
                 if let Some(flags) = progress_flags {
-                    let decoded_flags =
-                        data.progress_flags.iter().fold(0, |acc, &b| (acc << 1) | b as u8);
-                    if flags != decoded_flags {
+                    if flags != data.progress_flags {
                         continue;
                     }
                 }
-                // Exclude codes that generate an invalid item.
-                if data.items.contains(&(dq1::ITEM_TABLE.len() as u8 - 1)) {
+                // If the checksum is kept, ignore any substitutions where it changes.
+                if keep_checksum
+                    && original.is_some_and(|original| original.checksum != data.checksum)
+                {
                     continue;
                 }
-                // Exclude codes that generate more than the maximum amount of herbs or keys.
-                if data.herbs > 6 || data.keys > 6 {
+                // Exclude codes that break a rule of the game, such as holding an invalid item.
+                if rule::has_errors(&data.validate()) {
                     continue;
                 }
 
-                substitutions.push((new_string.clone(), data));
+                let label =
+                    altered_label(&new_string, original.map(|original| original.diff(&data)));
+                let label = match fit(&data) {
+                    Some(fit) => format!("{} ({})", label, fit.describe()),
+                    None => label,
+                };
+                substitutions.push((label, data));
             }
         }
     }
 
     if !substitutions.is_empty() {
+        // Show the most plausible substitutions first.
+        // Scores are lowered by any conflicts with the reference saves.
+        let mut substitutions = dq1::score_candidates(substitutions)
+            .into_iter()
+            .map(|(label, data, score)| {
+                let penalty = fit(&data).map_or(0, |fit| fit.penalty);
+                (label, data, score - penalty)
+            })
+            .collect::<Vec<_>>();
+        substitutions.sort_by_key(|&(_, _, score)| Reverse(score));
+        let count = substitutions.len();
+        let groups = group_substitutions(substitutions, dq1::GameData::state_difference);
         return Ok(format!(
-            "Found {} substitution(s):\n\n{}",
-            substitutions.len(),
-            dq1::tabulate_game_data(substitutions, input)
+            "Found {} substitution(s) with {} distinct game state(s):\n\n{}",
+            count,
+            groups.len(),
+            dq1::tabulate_game_data(groups, input)
         ));
     }
 
@@ -109,22 +504,58 @@ fn process_dq1(
 
 fn process_dq2(
     input: &str,
-    name: Option<String>,
-    progress_flags: Option<u8>,
+    name: Option<[char; 4]>,
+    progress_flags: Option<&[bool]>,
     keep_checksum: bool,
+    references: &[String],
 ) -> Result<String, String> {
     if let Ok(result) = dq2::decode_jumon(input) {
         if let Ok(data) = dq2::GameData::from_bytes(result.as_slice()) {
             return Ok(format!(
                 "The password is already valid:\n\n{}",
-                dq2::tabulate_game_data(vec![(input.to_string(), data)], input)
+                dq2::tabulate_game_data(
+                    dq2::score_candidates(vec![(input.to_string(), data)]),
+                    input
+                )
             ));
         }
     }
 
+    // Other saves from the playthrough, whose hero name the substitutions must share
+    let references = references
+        .iter()
+        .map(|password| {
+            decode_dq2(password)
+                .map_err(|err| format!("Invalid reference password {}: {}", password, err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let name = match references.first() {
+        Some(first)
+            if references.iter().any(|reference| reference.hero_name != first.hero_name) =>
+        {
+            return Err("The reference passwords have different names.".to_string());
+        }
+        Some(first) if name.is_some_and(|name| name != first.hero_name) => {
+            return Err("The name differs from the reference passwords.".to_string());
+        }
+        Some(first) => Some(first.hero_name),
+        None => name,
+    };
+    let timeline = references.iter().map(|reference| reference.progress()).collect::<Vec<_>>();
+    let fit = |data: &dq2::GameData| {
+        (!timeline.is_empty()).then(|| score::timeline_fit(&data.progress(), &timeline))
+    };
+
+    // The input as it stands, to explain which fields each substitution alters
+    let original = dq2::decode_unchecked(input).ok();
+    if keep_checksum && original.is_none() {
+        return Err(
+            "The checksum cannot be kept, since the password cannot be decoded.".to_string()
+        );
+    }
+
     let mut substitutions: Vec<(String, dq2::GameData)> = Vec::new();
-    let max_index = if keep_checksum { input.len() - 1 } else { input.len() };
-    for input_index_to_replace in 0..max_index {
+    for input_index_to_replace in 0..input.chars().count() {
         for moji in &dq2::JUMON_MOJI_TABLE {
             let mut new_string = String::with_capacity(input.len());
             for (input_index, input_character) in input.chars().enumerate() {
@@ -137,113 +568,1007 @@ fn process_dq2(
             if let Ok(decoded) = dq2::decode_jumon(&new_string) {
                 if let Ok(data) = dq2::GameData::from_bytes(decoded.as_slice()) {
                     // If the player name is known, ignore any substitutions where it is wrong.
-                    if let Some(ref player_name) = name {
-                        let player_name_chars = player_name.chars().collect::<Vec<char>>();
-                        if player_name_chars != data.hero_name {
+                    if let Some(player_name) = name {
+                        if player_name != data.hero_name {
                             continue;
                         }
                     }
                     // If progress flags are specified, ignore any substitutions where they change.
                     if let Some(flags) = progress_flags {
-                        let decoded_flags =
-                            data.progress_flags.iter().fold(0, |acc, &b| (acc << 1) | b as u8);
-                        if flags != decoded_flags {
+                        if flags != data.progress_flags {
                             continue;
                         }
                     }
-                    // Exclude codes that generate an invalid hero name.
-                    if data.hero_name.iter().any(|&c| c == '\0') {
-                        continue;
-                    }
-                    // Exclude codes that generate an invalid location.
-                    if dq2::LOCATION_TABLE.get(data.location as usize).is_none() {
+                    // If the checksum is kept, ignore any substitutions where it changes.
+                    if keep_checksum
+                        && original.is_some_and(|original| original.checksum != data.checksum)
+                    {
                         continue;
                     }
-                    // Exclude codes that generate more than the maximum amount of experience.
-                    if data.hero_experience > 1000000
-                        || data.prince_experience > 1000000
-                        || data.princess_experience > 1000000
-                    {
+                    // Exclude codes that break a rule of the game, such as an invalid location.
+                    if rule::has_errors(&data.validate()) {
                         continue;
                     }
 
-                    substitutions.push((new_string.clone(), data));
+                    let label =
+                        altered_label(&new_string, original.map(|original| original.diff(&data)));
+                    let label = match fit(&data) {
+                        Some(fit) => format!("{} ({})", label, fit.describe()),
+                        None => label,
+                    };
+                    substitutions.push((label, data));
                 }
             }
         }
     }
 
     if !substitutions.is_empty() {
+        // Show the most plausible substitutions first.
+        // Scores are lowered by any conflicts with the reference saves.
+        let mut substitutions = dq2::score_candidates(substitutions)
+            .into_iter()
+            .map(|(label, data, score)| {
+                let penalty = fit(&data).map_or(0, |fit| fit.penalty);
+                (label, data, score - penalty)
+            })
+            .collect::<Vec<_>>();
+        substitutions.sort_by_key(|&(_, _, score)| Reverse(score));
+        let count = substitutions.len();
+        let groups = group_substitutions(substitutions, dq2::GameData::state_difference);
         return Ok(format!(
-            "Found {} substitution(s):\n\n{}",
-            substitutions.len(),
-            dq2::tabulate_game_data(substitutions, input)
+            "Found {} substitution(s) with {} distinct game state(s):\n\n{}",
+            count,
+            groups.len(),
+            dq2::tabulate_game_data(groups, input)
         ));
     }
 
     Err("Recovery failed.".to_string())
 }
 
-fn main() {
-    let mut args: Vec<String> = env::args().collect();
-    let program = args[0].clone();
-    args = args[1..].to_vec();
+/// Labels a substitution with the fields it alters, if the input could be decoded.
+fn altered_label(password: &str, changes: Option<Vec<diff::Change>>) -> String {
+    match changes {
+        Some(changes) if !changes.is_empty() => {
+            let fields = changes.into_iter().map(|change| change.field).collect::<Vec<_>>();
+            format!("{} (altered: {})", password, fields.join(", "))
+        }
+        _ => password.to_string(),
+    }
+}
 
-    if args.is_empty() {
-        eprintln!("usage: {} [--name <name>] [--flags <flags>] [--keep-checksum] <input>", program);
-        return;
+/// Groups substitutions that decode to the same game state under the first of each group,
+/// listing the other passwords on separate lines of its label along with how they differ.
+fn group_substitutions<T>(
+    substitutions: Vec<(String, T, i32)>,
+    difference: fn(&T, &T) -> Option<&'static str>,
+) -> Vec<(String, T, i32)> {
+    let mut groups: Vec<(String, T, i32)> = Vec::new();
+    for (password, data, score) in substitutions {
+        let group = groups.iter_mut().find_map(|(label, representative, _)| {
+            difference(representative, &data).map(|difference| (label, difference))
+        });
+        match group {
+            Some((label, difference)) => {
+                label.push_str(&format!("\n{} ({})", password, difference));
+            }
+            None => groups.push((password, data, score)),
+        }
+    }
+    groups
+}
+
+/// Decodes a valid DQ1 password into game data.
+fn decode_dq1(input: &str) -> Result<dq1::GameData, String> {
+    if input.chars().count() != 20 {
+        return Err("Invalid input (DQ1 passwords are 20 characters long).".to_string());
+    }
+    let decoded = dq1::decode_jumon(input)?;
+    Ok(dq1::GameData::from_bytes(decoded.as_slice()))
+}
+
+/// Decodes a valid DQ2 password into game data.
+fn decode_dq2(input: &str) -> Result<dq2::GameData, String> {
+    if !(18..=dq2::MAX_JUMON_LENGTH).contains(&input.chars().count()) {
+        return Err(format!(
+            "Invalid input (DQ2 passwords are 18 to {} characters long).",
+            dq2::MAX_JUMON_LENGTH
+        ));
+    }
+    let decoded = dq2::decode_jumon(input)?;
+    dq2::GameData::from_bytes(decoded.as_slice()).map_err(|_| "Invalid game data.".to_string())
+}
+
+/// Determines which game a password belongs to, preferring the one it is valid for.
+fn detect_game(input: &str, game: Option<Game>) -> Result<Game, String> {
+    if let Some(game) = game {
+        return Ok(game);
+    }
+
+    let input_length = input.chars().count();
+    if input_length == 20 && input.chars().all(|c| dq1::JUMON_MOJI_TABLE.contains(&c)) {
+        if decode_dq1(input).is_err() && decode_dq2(input).is_ok() {
+            Ok(Game::Dq2)
+        } else {
+            Ok(Game::Dq1)
+        }
+    } else if (18..=dq2::MAX_JUMON_LENGTH).contains(&input_length)
+        && input.chars().all(|c| dq2::JUMON_MOJI_TABLE.contains(&c))
+    {
+        Ok(Game::Dq2)
+    } else {
+        Err("Invalid input.".to_string())
+    }
+}
+
+fn print_game(game: Game) {
+    match game {
+        Game::Dq1 => println!("{}", "DQ1".to_owned().bold().purple()),
+        Game::Dq2 => println!("{}", "DQ2".to_owned().bold().red()),
     }
+}
 
-    // Parse optional options to constrain substitutions.
-    let mut name: Option<String> = None;
-    let mut progress_flags: Option<u8> = None;
-    let mut keep_checksum: bool = false;
-    while args.len() >= 2 {
-        let arg: &str = &args[0];
-        match arg {
-            "--name" | "-n" => {
-                name = Some(split_dakuten(&args[1]));
-                args = args[2..].to_vec();
+/// Formats game data as `field=value` lines, readable as a state file.
+/// Derived values are written as comments, since they cannot be set.
+fn format_state<F: Display, D: Display>(
+    game: Game,
+    fields: Vec<(F, String)>,
+    derived: Vec<(D, String)>,
+) -> String {
+    let mut lines = vec![format!("game={}", if game == Game::Dq1 { "dq1" } else { "dq2" })];
+    for (field, value) in fields {
+        lines.push(format!("{}={}", field, value));
+    }
+    for (field, value) in derived {
+        lines.push(format!("# {}={}", field, value));
+    }
+    lines.join("\n")
+}
+
+fn check(input: &str, game: Option<Game>) -> Result<String, String> {
+    let game = detect_game(input, game)?;
+    print_game(game);
+    let result = match game {
+        Game::Dq1 => decode_dq1(input).map(|data| {
+            format!(
+                "The password is valid:\n\n{}",
+                dq1::tabulate_game_data(
+                    dq1::score_candidates(vec![(input.to_string(), data)]),
+                    input
+                )
+            )
+        }),
+        Game::Dq2 => decode_dq2(input).map(|data| {
+            format!(
+                "The password is valid:\n\n{}",
+                dq2::tabulate_game_data(
+                    dq2::score_candidates(vec![(input.to_string(), data)]),
+                    input
+                )
+            )
+        }),
+    };
+    note_famous(game, input, result)
+}
+
+/// Adds a note to the output or error if the input is or resembles a well-known password.
+fn note_famous(game: Game, input: &str, result: Result<String, String>) -> Result<String, String> {
+    let Some(note) = famous::describe(game, input) else {
+        return result;
+    };
+    result
+        .map(|output| format!("{}\n\n{}", note, output))
+        .map_err(|err| format!("{}\n\n{}", err, note))
+}
+
+fn recover(args: &RecoverArgs, game: Option<Game>) -> Result<String, String> {
+    let input = &input::join_password(&args.password.password);
+    let flags = args.flags.as_deref();
+    let references = match args.reference {
+        Some(ref path) => read_reference_file(path)?,
+        None => vec![],
+    };
+
+    // The number of progress flags given also determines the game.
+    let game = match flags.map(|flags| flags.len()) {
+        Some(length) if length == dq1::PROGRESS_FLAG_TABLE.len() && game != Some(Game::Dq2) => {
+            Some(Game::Dq1)
+        }
+        Some(length) if length == dq2::PROGRESS_FLAG_TABLE.len() && game != Some(Game::Dq1) => {
+            Some(Game::Dq2)
+        }
+        Some(length) => return Err(format!("Unexpected number of progress flags: {}", length)),
+        None => game,
+    };
+
+    let input_length = input.chars().count();
+    if game != Some(Game::Dq2)
+        && input_length == 20
+        && input.chars().all(|c| dq1::JUMON_MOJI_TABLE.contains(&c))
+    {
+        let dq1_res = process_dq1(input, args.name, flags, args.keep_checksum, &references);
+        if dq1_res.is_ok() || game == Some(Game::Dq1) {
+            print_game(Game::Dq1);
+            return note_famous(Game::Dq1, input, dq1_res);
+        }
+    }
+
+    if game != Some(Game::Dq1)
+        && (18..=dq2::MAX_JUMON_LENGTH).contains(&input_length)
+        && input.chars().all(|c| dq2::JUMON_MOJI_TABLE.contains(&c))
+    {
+        print_game(Game::Dq2);
+        let dq2_res = process_dq2(input, args.name, flags, args.keep_checksum, &references);
+        return note_famous(Game::Dq2, input, dq2_res);
+    }
+
+    Err("Invalid input.".to_string())
+}
+
+fn decode(args: &DecodeArgs, game: Option<Game>) -> Result<String, String> {
+    let input = &input::join_password(&args.password.password);
+    let game = detect_game(input, game)?;
+    if args.explain {
+        print_game(game);
+        return match game {
+            Game::Dq1 => dq1::explain(input),
+            Game::Dq2 => dq2::explain(input),
+        };
+    }
+    match game {
+        Game::Dq1 => {
+            let data = decode_dq1(input)?;
+            if args.state {
+                return Ok(format_state(game, data.fields(), data.stats().fields()));
             }
-            "--flags" | "-f" => {
-                progress_flags = args[1].clone().parse::<u8>().ok();
-                args = args[2..].to_vec();
+            print_game(game);
+            let table = dq1::tabulate_game_data(
+                dq1::score_candidates(vec![(input.to_string(), data)]),
+                input,
+            );
+            note_famous(game, input, Ok(table))
+        }
+        Game::Dq2 => {
+            let data = decode_dq2(input)?;
+            if args.state {
+                let derived = data
+                    .members()
+                    .into_iter()
+                    .flat_map(|member| data.stats(member).fields(member))
+                    .collect();
+                return Ok(format_state(game, data.fields(), derived));
             }
-            "--keep-checksum" | "-k" => {
-                keep_checksum = true;
-                args = args[1..].to_vec();
+            print_game(game);
+            let table = dq2::tabulate_game_data(
+                dq2::score_candidates(vec![(input.to_string(), data)]),
+                input,
+            );
+            note_famous(game, input, Ok(table))
+        }
+    }
+}
+
+/// Reads a file of passwords, one per line, ignoring blank lines, `#` comments and spacing.
+fn read_reference_file(path: &str) -> Result<Vec<String>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read reference file {}: {}", path, err))?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap().split_whitespace().collect::<String>())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Reads the `field=value` assignments of a state file.
+fn read_state_file(path: &str) -> Result<Vec<(String, String)>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read state file {}: {}", path, err))?;
+    input::parse_state(&contents)
+}
+
+/// Removes the `game` assignment, returning the game unless overridden on the command line.
+fn take_game(assignments: &mut Vec<(String, String)>, game: Option<Game>) -> Result<Game, String> {
+    let mut state_game = None;
+    assignments.retain(|(field, value)| {
+        if field != "game" {
+            return true;
+        }
+        state_game = Game::from_str(value, true).ok();
+        false
+    });
+    game.or(state_game)
+        .ok_or_else(|| "The game must be specified with --game or in the state file.".to_string())
+}
+
+fn encode(args: &EncodeArgs, game: Option<Game>) -> Result<String, String> {
+    let mut assignments = match args.state {
+        Some(ref path) => read_state_file(path)?,
+        None => vec![],
+    };
+    assignments.extend(args.assignments.iter().cloned());
+    let game = take_game(&mut assignments, game)?;
+
+    print_game(game);
+    match game {
+        Game::Dq1 => {
+            let mut data = dq1::GameData::from_assignments(&assignments)?;
+            let output = dq1::encode_game_data(&data);
+            data.update_checksum();
+            Ok(dq1::tabulate_game_data(
+                dq1::score_candidates(vec![(output.clone(), data)]),
+                &output,
+            ))
+        }
+        Game::Dq2 => {
+            let mut data = dq2::GameData::from_assignments(&assignments)?;
+            let output = dq2::encode_game_data(&data);
+            data.update_checksum();
+            Ok(dq2::tabulate_game_data(
+                dq2::score_candidates(vec![(output.clone(), data)]),
+                &output,
+            ))
+        }
+    }
+}
+
+fn edit(args: &EditArgs, game: Option<Game>) -> Result<String, String> {
+    let input = &input::join_password(&args.password.password);
+    let game = detect_game(input, game)?;
+    print_game(game);
+    match game {
+        Game::Dq1 => {
+            let mut data = decode_dq1(input)?;
+            for (field, value) in args.assignments.iter() {
+                data.set_field(field, value)?;
             }
-            _ => {
-                break;
+            if !args.keep_checksum {
+                data.update_checksum();
             }
+            let output = dq1::encode_jumon(&data.to_bytes());
+            Ok(dq1::tabulate_game_data(dq1::score_candidates(vec![(output, data)]), input))
+        }
+        Game::Dq2 => {
+            let mut data = decode_dq2(input)?;
+            for (field, value) in args.assignments.iter() {
+                data.set_field(field, value)?;
+            }
+            if !args.keep_checksum {
+                data.update_checksum();
+            }
+            let output = dq2::encode_jumon(&data.to_bytes());
+            Ok(dq2::tabulate_game_data(dq2::score_candidates(vec![(output, data)]), input))
         }
     }
+}
 
-    // Join all arguments to account for any spacing within the password.
-    let input_string = &args.join("").split_whitespace().collect::<String>();
-    let input_length = input_string.chars().count();
-    let result: Result<String, String> =
-        if input_string.chars().all(|c| dq1::JUMON_MOJI_TABLE.contains(&c)) && input_length == 20 {
-            let dq1_res = process_dq1(input_string, name.clone(), progress_flags, keep_checksum);
-            if dq1_res.is_err() {
-                println!("{}", "DQ2".to_owned().bold().red());
-                process_dq2(input_string, name, progress_flags, keep_checksum)
-            } else {
-                println!("{}", "DQ1".to_owned().bold().purple());
-                dq1_res
-            }
-        } else if input_length >= 18
-            && input_length <= 52
-            && input_string.chars().all(|c| dq2::JUMON_MOJI_TABLE.contains(&c))
-        {
-            println!("{}", "DQ2".to_owned().bold().red());
-            process_dq2(input_string, name, progress_flags, keep_checksum)
+fn diff(args: &DiffArgs, game: Option<Game>) -> Result<String, String> {
+    let a = &input::join_password(std::slice::from_ref(&args.a));
+    let b = &input::join_password(std::slice::from_ref(&args.b));
+    let game = detect_game(a, game)?;
+    print_game(game);
+    let changes = match game {
+        Game::Dq1 => decode_dq1(a)?.diff(&decode_dq1(b)?),
+        Game::Dq2 => decode_dq2(a)?.diff(&decode_dq2(b)?),
+    };
+    Ok(diff::tabulate_changes(&changes))
+}
+
+fn repair(input: &str, game: Option<Game>) -> Result<String, String> {
+    let game = detect_game(input, game)?;
+    let (repaired, table) = match game {
+        Game::Dq1 => {
+            let repaired = dq1::repair_checksum(input)?;
+            let data = decode_dq1(&repaired)?;
+            (
+                repaired.clone(),
+                dq1::tabulate_game_data(dq1::score_candidates(vec![(repaired, data)]), input),
+            )
+        }
+        Game::Dq2 => {
+            let repaired = dq2::repair_checksum(input)?;
+            let data = decode_dq2(&repaired)?;
+            (
+                repaired.clone(),
+                dq2::tabulate_game_data(dq2::score_candidates(vec![(repaired, data)]), input),
+            )
+        }
+    };
+
+    print_game(game);
+    let changed = input
+        .chars()
+        .zip(repaired.chars())
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(index, _)| (index + 1).to_string())
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return Ok(format!("The checksum is already valid:\n\n{}", table));
+    }
+    Ok(format!(
+        "Repaired the checksum by changing {} character(s) (at {}):\n\n{}",
+        changed.len(),
+        changed.join(", "),
+        table
+    ))
+}
+
+/// Gives the character table and packing of a game's passwords.
+fn codec(game: Game) -> notation::Codec {
+    match game {
+        Game::Dq1 => notation::Codec {
+            table: &dq1::JUMON_MOJI_TABLE,
+            pack: dq1::packed_bytes,
+            unpack: dq1::password_from_bytes,
+        },
+        Game::Dq2 => notation::Codec {
+            table: &dq2::JUMON_MOJI_TABLE,
+            pack: dq2::packed_bytes,
+            unpack: dq2::password_from_bytes,
+        },
+    }
+}
+
+/// Rewrites the passwords given to a command in `format` as kana, before the command runs.
+fn read_passwords(
+    command: &mut Command,
+    format: notation::Format,
+    game: Option<Game>,
+) -> Result<(), String> {
+    if format == notation::Format::Kana {
+        return Ok(());
+    }
+    let game = game.ok_or_else(|| {
+        "The game must be specified with --game to read indices or bytes.".to_string()
+    })?;
+    let codec = codec(game);
+    let read = |password: &mut Vec<String>| -> Result<(), String> {
+        if !password.is_empty() {
+            *password = vec![notation::to_kana(password, format, &codec)?];
+        }
+        Ok(())
+    };
+    let read_one = |password: &mut String| -> Result<(), String> {
+        *password = notation::to_kana(std::slice::from_ref(password), format, &codec)?;
+        Ok(())
+    };
+    match command {
+        Command::Check(args)
+        | Command::Repair(args)
+        | Command::Convert(ConvertArgs { password: args, .. })
+        | Command::Recover(RecoverArgs { password: args, .. })
+        | Command::Decode(DecodeArgs { password: args, .. })
+        | Command::Edit(EditArgs { password: args, .. })
+        | Command::Sensitivity(args)
+        | Command::Plan(args)
+        | Command::Movie(MovieArgs { password: args, .. })
+        | Command::Render(RenderArgs { password: args, .. }) => read(&mut args.password),
+        Command::Variants(args) | Command::Easiest(args) => read(&mut args.password),
+        Command::Diff(DiffArgs { a, b }) | Command::Merge(MergeArgs { a, b }) => {
+            read_one(a)?;
+            read_one(b)
+        }
+        Command::Complete(_) | Command::Vanity(_) | Command::Screenshot(_) => {
+            Err("Partial passwords and phrases can only be given as kana.".to_string())
+        }
+        Command::Encode(_) | Command::Optimise(_) | Command::Import(_) | Command::Catalogue => {
+            Ok(())
+        }
+    }
+}
+
+fn convert(args: &ConvertArgs, game: Option<Game>) -> Result<String, String> {
+    let input = &input::join_password(&args.password.password);
+    let game = detect_game(input, game)?;
+    print_game(game);
+    notation::from_kana(input, args.output_format, &codec(game))
+}
+
+fn merge(args: &MergeArgs, game: Option<Game>) -> Result<String, String> {
+    let a = &input::join_password(std::slice::from_ref(&args.a));
+    let b = &input::join_password(std::slice::from_ref(&args.b));
+    let aligned = merge::align(a, b)?;
+    let combinations = merge::combinations(&aligned)?;
+    let game = detect_game(&combinations[0].0, game)?;
+    print_game(game);
+
+    let label = |password: &str, first: usize, second: usize| {
+        if first + second == 0 {
+            password.to_string()
         } else {
-            Err("Invalid input.".to_string())
+            format!("{} ({} from first, {} from second)", password, first, second)
+        }
+    };
+    let (best, count, table) = match game {
+        Game::Dq1 => {
+            let merged = combinations
+                .iter()
+                .filter_map(|(password, first, second)| {
+                    let data = decode_dq1(password).ok()?;
+                    // Exclude codes that break a rule of the game, as in recovery.
+                    (!rule::has_errors(&data.validate()))
+                        .then(|| (label(password, *first, *second), data))
+                })
+                .collect::<Vec<_>>();
+            let mut merged = dq1::score_candidates(merged);
+            merged.sort_by_key(|&(_, _, score)| Reverse(score));
+            let best = merged.first().map(|(label, _, _)| label_password(label).to_string());
+            let count = merged.len();
+            let groups = group_substitutions(merged, dq1::GameData::state_difference);
+            (best, count, dq1::tabulate_game_data(groups, a))
+        }
+        Game::Dq2 => {
+            let merged = combinations
+                .iter()
+                .filter_map(|(password, first, second)| {
+                    let data = decode_dq2(password).ok()?;
+                    // Exclude codes that break a rule of the game, as in recovery.
+                    (!rule::has_errors(&data.validate()))
+                        .then(|| (label(password, *first, *second), data))
+                })
+                .collect::<Vec<_>>();
+            let mut merged = dq2::score_candidates(merged);
+            merged.sort_by_key(|&(_, _, score)| Reverse(score));
+            let best = merged.first().map(|(label, _, _)| label_password(label).to_string());
+            let count = merged.len();
+            let groups = group_substitutions(merged, dq2::GameData::state_difference);
+            (best, count, dq2::tabulate_game_data(groups, a))
+        }
+    };
+
+    if count == 0 {
+        return Err(format!(
+            "None of the {} combination(s) of the copies is valid; try recovering each copy.",
+            combinations.len()
+        ));
+    }
+    let result =
+        Ok(format!("Found {} valid combination(s) of {}:\n\n{}", count, combinations.len(), table));
+    match best {
+        Some(best) => note_famous(game, &best, result),
+        None => result,
+    }
+}
+
+/// Gives the password a label starts with, before any notes about it.
+fn label_password(label: &str) -> &str {
+    label.split_whitespace().next().unwrap_or_default()
+}
+
+fn complete(args: &CompleteArgs, game: Option<Game>) -> Result<String, String> {
+    let input = input::join_password(&args.password);
+    let input_length = input.chars().count();
+    // A partial password is too short to tell the games apart, so only a DQ2 length
+    // or a password longer than DQ1's implies DQ2
+    let game = game.unwrap_or(match args.length {
+        Some(length) if length != 20 => Game::Dq2,
+        None if input_length > 20 => Game::Dq2,
+        _ => Game::Dq1,
+    });
+    print_game(game);
+
+    let flags = args.flags.as_deref();
+    let completions = match game {
+        Game::Dq1 => {
+            let completions = dq1::complete(&input, args.name, flags, &args.items)?;
+            let mut completions = dq1::score_candidates(completions);
+            completions.sort_by_key(|&(_, _, score)| Reverse(score));
+            let best = completions.first().map(|(label, _, _)| label.clone());
+            let count = completions.len();
+            completions.truncate(args.limit);
+            let groups = group_substitutions(completions, dq1::GameData::state_difference);
+            (best, count, dq1::tabulate_game_data(groups, &input))
+        }
+        Game::Dq2 => {
+            let length = args.length.ok_or_else(|| {
+                format!(
+                    "Give the length of the full DQ2 password with --length (18 to {}).",
+                    dq2::MAX_JUMON_LENGTH
+                )
+            })?;
+            let completions = dq2::complete(&input, length, args.name, flags, &args.items)?;
+            let mut completions = dq2::score_candidates(completions);
+            completions.sort_by_key(|&(_, _, score)| Reverse(score));
+            let best = completions.first().map(|(label, _, _)| label.clone());
+            let count = completions.len();
+            completions.truncate(args.limit);
+            let groups = group_substitutions(completions, dq2::GameData::state_difference);
+            (best, count, dq2::tabulate_game_data(groups, &input))
+        }
+    };
+
+    match completions {
+        (_, 0, _) => Err("No completions found.".to_string()),
+        (best, count, table) => {
+            let result = Ok(format!(
+                "Found {} completion(s); the {} most plausible:\n\n{}",
+                count,
+                count.min(args.limit),
+                table
+            ));
+            match best {
+                Some(best) => note_famous(game, &best, result),
+                None => result,
+            }
+        }
+    }
+}
+
+fn screenshot(args: &ScreenshotArgs, game: Option<Game>) -> Result<String, String> {
+    let bitmap = ocr::Bitmap::read_png(&args.image, args.scale)?;
+    let bitmap = match args.crop {
+        Some(region) => bitmap.crop(region)?,
+        None => bitmap,
+    };
+    let font = match args.font {
+        Some(ref path) => ocr::Font::read_png(path)?,
+        None => ocr::Font::builtin(),
+    };
+    let tables: &[&[char; 64]] = match game {
+        Some(Game::Dq1) => &[&dq1::JUMON_MOJI_TABLE],
+        Some(Game::Dq2) => &[&dq2::JUMON_MOJI_TABLE],
+        None => &[&dq1::JUMON_MOJI_TABLE, &dq2::JUMON_MOJI_TABLE],
+    };
+    let password = ocr::read_password(&bitmap, &font, tables);
+    if password.is_empty() {
+        return Err("No characters were found in the screenshot.".to_string());
+    }
+
+    let unsure = password.chars().filter(|&c| c == ocr::UNSURE).count();
+    println!("Read {} character(s), {} unsure: {}", password.chars().count(), unsure, password);
+    if unsure == 0 {
+        let args = RecoverArgs {
+            name: None,
+            flags: None,
+            keep_checksum: false,
+            reference: None,
+            password: PasswordArgs { password: vec![password] },
         };
+        return recover(&args, game);
+    }
+    let args = CompleteArgs {
+        name: None,
+        flags: None,
+        items: vec![],
+        // The whole password screen was read, so its length is known
+        length: Some(password.chars().count()),
+        limit: 10,
+        password: vec![password],
+    };
+    complete(&args, game)
+}
+
+fn optimise(args: &OptimiseArgs, game: Option<Game>) -> Result<String, String> {
+    let mut assignments = match args.state {
+        Some(ref path) => read_state_file(path)?,
+        None => vec![],
+    };
+    assignments.extend(args.assignments.iter().cloned());
+    let game = take_game(&mut assignments, game)?;
+    print_game(game);
+
+    let (table, outcomes, binding) = match game {
+        Game::Dq1 => {
+            let hooks = optimise::Hooks {
+                from_assignments: dq1::GameData::from_assignments,
+                set_field: dq1::GameData::set_field,
+                validate: dq1::GameData::validate,
+                objectives: &dq1::OBJECTIVES,
+            };
+            let optimum = optimise::optimise(&hooks, &assignments, &args.fields)?;
+            let mut data = optimum.data;
+            let output = dq1::encode_game_data(&data);
+            data.update_checksum();
+            let table = dq1::tabulate_game_data(
+                dq1::score_candidates(vec![(output.clone(), data)]),
+                &output,
+            );
+            (table, optimum.outcomes, optimum.binding)
+        }
+        Game::Dq2 => {
+            let hooks = optimise::Hooks {
+                from_assignments: dq2::GameData::from_assignments,
+                set_field: dq2::GameData::set_field,
+                validate: dq2::GameData::validate,
+                objectives: &dq2::OBJECTIVES,
+            };
+            let optimum = optimise::optimise(&hooks, &assignments, &args.fields)?;
+            let mut data = optimum.data;
+            let output = dq2::encode_game_data(&data);
+            data.update_checksum();
+            let table = dq2::tabulate_game_data(
+                dq2::score_candidates(vec![(output.clone(), data)]),
+                &output,
+            );
+            (table, optimum.outcomes, optimum.binding)
+        }
+    };
+
+    let binding = match binding.is_empty() {
+        true => "No set field was binding.".to_string(),
+        false => format!("Binding set fields: {}", binding.join(", ")),
+    };
+    Ok(format!("{}\n{}\n{}", table, optimise::tabulate_outcomes(&outcomes), binding))
+}
+
+fn vanity(args: &VanityArgs, game: Option<Game>) -> Result<String, String> {
+    let phrase = input::join_password(&args.phrase);
+    let mut assignments = args.assignments.clone();
+    let game = take_game(&mut assignments, game)?;
+    print_game(game);
+
+    // Only the phrase is shown as given, so that the solved characters stand out
+    let shown = format!("{}{}", "？".repeat(args.position), phrase);
+    // Gather more passwords than asked for, and show the most plausible, then the
+    // quickest to enter
+    let pool = args.limit * vanity::CANDIDATES_PER_RESULT;
+    let presses = |password: &str| {
+        keypad::plan(password_grid(game), password)
+            .map_or(usize::MAX, |steps| keypad::press_count(&steps))
+    };
+    let (count, table) = match game {
+        Game::Dq1 => {
+            let found = dq1::vanity(&phrase, args.position, &assignments, args.min_level, pool)?;
+            let mut found = dq1::score_candidates(found);
+            found.sort_by_key(|(password, _, score)| (Reverse(*score), presses(password)));
+            found.truncate(args.limit);
+            (found.len(), dq1::tabulate_game_data(found, &shown))
+        }
+        Game::Dq2 => {
+            let found = dq2::vanity(&phrase, args.position, &assignments, args.min_level, pool)?;
+            let mut found = dq2::score_candidates(found);
+            found.sort_by_key(|(password, _, score)| (Reverse(*score), presses(password)));
+            found.truncate(args.limit);
+            (found.len(), dq2::tabulate_game_data(found, &shown))
+        }
+    };
+
+    if count == 0 {
+        return Err("No valid passwords contain the phrase there.".to_string());
+    }
+    Ok(format!("Found {} password(s):\n\n{}", count, table))
+}
+
+fn sensitivity(input: &str, game: Option<Game>) -> Result<String, String> {
+    let game = detect_game(input, game)?;
+    let report = match game {
+        Game::Dq1 => sensitivity::sensitivity(
+            input,
+            &dq1::JUMON_MOJI_TABLE,
+            dq1::decode_unchecked,
+            dq1::GameData::diff,
+        )?,
+        Game::Dq2 => sensitivity::sensitivity(
+            input,
+            &dq2::JUMON_MOJI_TABLE,
+            dq2::decode_unchecked,
+            dq2::GameData::diff,
+        )?,
+    };
+    print_game(game);
+    Ok(sensitivity::tabulate_sensitivity(input, &report))
+}
+
+fn password_grid(game: Game) -> &'static keypad::Grid {
+    match game {
+        Game::Dq1 => &dq1::PASSWORD_GRID,
+        Game::Dq2 => &dq2::PASSWORD_GRID,
+    }
+}
+
+fn plan(input: &str, game: Option<Game>) -> Result<String, String> {
+    let game = detect_game(input, game)?;
+    let grid = password_grid(game);
+    let steps = keypad::plan(grid, input)?;
+    print_game(game);
+    Ok(format!(
+        "{}Total: {} press(es) for {} key(s)",
+        keypad::tabulate_steps(&steps),
+        keypad::press_count(&steps),
+        steps.len()
+    ))
+}
+
+fn movie(args: &MovieArgs, game: Option<Game>) -> Result<String, String> {
+    let input = &input::join_password(&args.password.password);
+    let game = detect_game(input, game)?;
+    let grid = password_grid(game);
+    let steps = keypad::plan(grid, input)?;
+    let timing = movie::Timing { lead: args.lead, hold: args.hold, release: args.release };
+    let frames = movie::frames(&steps, &timing);
+
+    // Play the movie back on the grid to make sure it enters the password as given
+    if movie::replay(grid, &frames).as_deref() != Ok(input) {
+        return Err("The movie does not enter the password; check the timing.".to_string());
+    }
+    fs::write(&args.output, movie::format_fm2(&frames, &args.rom))
+        .map_err(|err| format!("Failed to write movie {}: {}", args.output, err))?;
+
+    print_game(game);
+    Ok(format!(
+        "Wrote {} frame(s) with {} press(es) to {}",
+        frames.len(),
+        keypad::press_count(&steps),
+        args.output
+    ))
+}
+
+fn import(args: &ImportArgs, game: Option<Game>) -> Result<String, String> {
+    // Nothing in the RAM tells the games apart reliably
+    let game = game.ok_or("The game must be specified with --game to read RAM.")?;
+    let ram = savestate::read_ram(&args.file)?;
+
+    print_game(game);
+    match game {
+        Game::Dq1 => {
+            let mut data = dq1::GameData::from_ram(&ram)?;
+            let output = dq1::encode_game_data(&data);
+            data.update_checksum();
+            Ok(dq1::tabulate_game_data(
+                dq1::score_candidates(vec![(output.clone(), data)]),
+                &output,
+            ))
+        }
+        Game::Dq2 => {
+            let mut data = dq2::GameData::from_ram(&ram)?;
+            let output = dq2::encode_game_data(&data);
+            data.update_checksum();
+            Ok(dq2::tabulate_game_data(
+                dq2::score_candidates(vec![(output.clone(), data)]),
+                &output,
+            ))
+        }
+    }
+}
+
+fn render(args: &RenderArgs, game: Option<Game>) -> Result<String, String> {
+    let input = &input::join_password(&args.password.password);
+    let game = detect_game(input, game)?;
+    let groups: &[usize] = match game {
+        Game::Dq1 => &dq1::PASSWORD_LAYOUT,
+        Game::Dq2 => &dq2::PASSWORD_LAYOUT,
+    };
+    let summary = match (args.summary, game) {
+        (false, _) => vec![],
+        (true, Game::Dq1) => {
+            let data = decode_dq1(input)?;
+            let name = data.name.iter().collect::<String>();
+            vec![format!("{}　LV {}　{}G", name.trim_end(), data.stats().level, data.gold)]
+        }
+        (true, Game::Dq2) => {
+            let data = decode_dq2(input)?;
+            let name = data.hero_name.iter().collect::<String>();
+            let level = data.stats(dq2::Member::Hero).level;
+            vec![format!("{}　LV {}　{}G", name.trim_end(), level, data.gold)]
+        }
+    };
+
+    let extension = args.output.rsplit_once('.').map(|(_, extension)| extension.to_lowercase());
+    let font = match args.font {
+        Some(ref path) => ocr::Font::read_png(path)?,
+        None => ocr::Font::builtin(),
+    };
+    let image = match extension.as_deref() {
+        Some("svg") => render::render_svg(input, groups, &summary, &font)?.into_bytes(),
+        Some("png") => render::render_png(input, groups, &summary, &font, args.scale.max(1))?,
+        _ => {
+            return Err(format!(
+                "Unsupported image format (expected .svg or .png): {}",
+                args.output
+            ))
+        }
+    };
+    fs::write(&args.output, image)
+        .map_err(|err| format!("Failed to write image {}: {}", args.output, err))?;
+
+    print_game(game);
+    Ok(format!("Wrote {}", args.output))
+}
+
+/// Lists every password for the game data of a password or state file, by encryption key.
+fn equivalent_passwords(
+    args: &SourceArgs,
+    game: Option<Game>,
+) -> Result<(Game, Vec<(u8, String)>), String> {
+    if let Some(ref path) = args.state {
+        let mut assignments = read_state_file(path)?;
+        let game = take_game(&mut assignments, game)?;
+        let passwords = match game {
+            Game::Dq1 => dq1::equivalent_passwords(&dq1::GameData::from_assignments(&assignments)?),
+            Game::Dq2 => dq2::equivalent_passwords(&dq2::GameData::from_assignments(&assignments)?),
+        };
+        return Ok((game, passwords));
+    }
+
+    let input = &input::join_password(&args.password);
+    let game = detect_game(input, game)?;
+    let passwords = match game {
+        Game::Dq1 => dq1::equivalent_passwords(&decode_dq1(input)?),
+        Game::Dq2 => dq2::equivalent_passwords(&decode_dq2(input)?),
+    };
+    Ok((game, passwords))
+}
+
+/// Formats passwords as a table along with how hard they are to copy by hand.
+fn tabulate_passwords(passwords: &[(u8, String)]) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["Key", "Password", "Confusable", "Dakuten"]);
+    for (key, password) in passwords {
+        let (confusable, dakuten) = kana::copy_difficulty(password);
+        table.add_row(row![key, password, confusable, dakuten]);
+    }
+    table.to_string()
+}
+
+fn variants(args: &SourceArgs, game: Option<Game>) -> Result<String, String> {
+    let (game, passwords) = equivalent_passwords(args, game)?;
+    print_game(game);
+    Ok(tabulate_passwords(&passwords))
+}
+
+fn easiest(args: &SourceArgs, game: Option<Game>) -> Result<String, String> {
+    let (game, passwords) = equivalent_passwords(args, game)?;
+    // Confusable kana are worse than dakuten on a tie, and the lowest key wins after that.
+    let easiest = passwords.into_iter().min_by_key(|(_, password)| {
+        let (confusable, dakuten) = kana::copy_difficulty(password);
+        (confusable + dakuten, confusable)
+    });
+    print_game(game);
+    Ok(tabulate_passwords(&easiest.into_iter().collect::<Vec<_>>()))
+}
+
+fn catalogue(game: Option<Game>) -> Result<String, String> {
+    let mut output = vec![];
+    if game != Some(Game::Dq2) {
+        output.push(format!("{}\n{}", "DQ1".to_owned().bold().purple(), dq1::tabulate_catalogue()));
+    }
+    if game != Some(Game::Dq1) {
+        output.push(format!("{}\n{}", "DQ2".to_owned().bold().red(), dq2::tabulate_catalogue()));
+    }
+    Ok(output.join("\n"))
+}
+
+fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+    if let Err(err) = read_passwords(&mut cli.command, cli.input_format, cli.game) {
+        eprintln!("{}", err);
+        return ExitCode::FAILURE;
+    }
+
+    let result: Result<String, String> = match cli.command {
+        Command::Check(ref args) => check(&input::join_password(&args.password), cli.game),
+        Command::Recover(ref args) => recover(args, cli.game),
+        Command::Decode(ref args) => decode(args, cli.game),
+        Command::Encode(ref args) => encode(args, cli.game),
+        Command::Edit(ref args) => edit(args, cli.game),
+        Command::Diff(ref args) => diff(args, cli.game),
+        Command::Repair(ref args) => repair(&input::join_password(&args.password), cli.game),
+        Command::Convert(ref args) => convert(args, cli.game),
+        Command::Merge(ref args) => merge(args, cli.game),
+        Command::Complete(ref args) => complete(args, cli.game),
+        Command::Screenshot(ref args) => screenshot(args, cli.game),
+        Command::Optimise(ref args) => optimise(args, cli.game),
+        Command::Vanity(ref args) => vanity(args, cli.game),
+        Command::Sensitivity(ref args) => {
+            sensitivity(&input::join_password(&args.password), cli.game)
+        }
+        Command::Plan(ref args) => plan(&input::join_password(&args.password), cli.game),
+        Command::Movie(ref args) => movie(args, cli.game),
+        Command::Import(ref args) => import(args, cli.game),
+        Command::Render(ref args) => render(args, cli.game),
+        Command::Variants(ref args) => variants(args, cli.game),
+        Command::Easiest(ref args) => easiest(args, cli.game),
+        Command::Catalogue => catalogue(cli.game),
+    };
 
     match result {
-        Ok(output) => println!("{}", output),
-        Err(err) => eprintln!("{}", err),
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
     }
 }
@@ -0,0 +1,141 @@
+/// Most characters one copy may lack compared with the other.
+const MAX_GAPS: usize = 2;
+
+/// Most positions at which the copies may disagree, so that the search stays quick.
+const MAX_DISAGREEMENTS: usize = 20;
+
+/// Characters of the first and second copies at one position, or `None` for a gap.
+pub(crate) type Pair = (Option<char>, Option<char>);
+
+/// Aligns two copies of a password position by position.
+///
+/// If one copy is shorter, it is padded with gaps (`None`) where it best matches the other,
+/// as when a character was skipped while copying.
+pub(crate) fn align(a: &str, b: &str) -> Result<Vec<Pair>, String> {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let (long, short, swapped) = if a.len() >= b.len() { (a, b, false) } else { (b, a, true) };
+    let gaps = long.len() - short.len();
+    if gaps > MAX_GAPS {
+        return Err(format!("The copies differ by more than {} characters in length.", MAX_GAPS));
+    }
+
+    // mismatches[i][j] is the fewest mismatches aligning long[..i] with short[..j]
+    let (n, m) = (long.len(), short.len());
+    let mut mismatches = vec![vec![usize::MAX; m + 1]; n + 1];
+    mismatches[0][0] = 0;
+    for i in 1..=n {
+        for j in i.saturating_sub(gaps)..=i.min(m) {
+            let gap = mismatches[i - 1][j];
+            let pair = match j {
+                0 => usize::MAX,
+                _ => {
+                    mismatches[i - 1][j - 1].saturating_add((long[i - 1] != short[j - 1]) as usize)
+                }
+            };
+            mismatches[i][j] = gap.min(pair);
+        }
+    }
+
+    // Walk back from the end, preferring pairs to gaps on ties
+    let mut aligned = vec![];
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        let pair = j > 0
+            && mismatches[i - 1][j - 1].saturating_add((long[i - 1] != short[j - 1]) as usize)
+                == mismatches[i][j];
+        if pair {
+            aligned.push((Some(long[i - 1]), Some(short[j - 1])));
+            j -= 1;
+        } else {
+            aligned.push((Some(long[i - 1]), None));
+        }
+        i -= 1;
+    }
+    aligned.reverse();
+
+    if swapped {
+        aligned = aligned.into_iter().map(|(long, short)| (short, long)).collect();
+    }
+    Ok(aligned)
+}
+
+/// Lists every password that takes each disagreeing character from either copy, with
+/// the number of characters taken from the first and second copies.
+///
+/// Where one copy has a gap, the character of the other copy is taken.
+pub(crate) fn combinations(aligned: &[Pair]) -> Result<Vec<(String, usize, usize)>, String> {
+    let disagreements = aligned
+        .iter()
+        .enumerate()
+        .filter(|(_, pair)| matches!(pair, (Some(a), Some(b)) if a != b))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if disagreements.len() > MAX_DISAGREEMENTS {
+        return Err(format!(
+            "The copies disagree at {} positions (at most {}).",
+            disagreements.len(),
+            MAX_DISAGREEMENTS
+        ));
+    }
+
+    let mut passwords = vec![];
+    for choice in 0..1_u32 << disagreements.len() {
+        // Each bit of `choice` takes the character of the second copy at a disagreement
+        let mut password = String::with_capacity(aligned.len() * 3);
+        for (index, &(a, b)) in aligned.iter().enumerate() {
+            let from_second = disagreements
+                .iter()
+                .position(|&disagreement| disagreement == index)
+                .is_some_and(|bit| choice >> bit & 1 != 0);
+            password.push(if from_second { b } else { a.or(b) }.unwrap());
+        }
+        let from_second = choice.count_ones() as usize;
+        passwords.push((password, disagreements.len() - from_second, from_second));
+    }
+    Ok(passwords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_copies_of_the_same_length_position_by_position() {
+        let aligned = align("あいう", "あえう").unwrap();
+        assert_eq!(
+            aligned,
+            [(Some('あ'), Some('あ')), (Some('い'), Some('え')), (Some('う'), Some('う'))]
+        );
+    }
+
+    #[test]
+    fn puts_a_gap_where_a_character_was_skipped() {
+        let aligned = align("あいうえお", "あうえお").unwrap();
+        assert_eq!(aligned[1], (Some('い'), None));
+        assert!(aligned.iter().all(|&(a, b)| b.is_none() || a == b));
+
+        // The order of the copies is kept when the first one is shorter
+        let swapped = align("あうえお", "あいうえお").unwrap();
+        assert_eq!(swapped[1], (None, Some('い')));
+    }
+
+    #[test]
+    fn rejects_copies_too_different_in_length() {
+        assert!(align("あいうえお", "あい").is_err());
+    }
+
+    #[test]
+    fn combines_every_choice_at_disagreements_and_fills_gaps() {
+        let aligned = align("かいうけこ", "かうえお").unwrap();
+        assert_eq!(aligned[1], (Some('い'), None));
+        let mut passwords = combinations(&aligned).unwrap();
+        passwords.sort();
+        let expected = [
+            ("かいうえお", 0, 2),
+            ("かいうえこ", 1, 1),
+            ("かいうけお", 1, 1),
+            ("かいうけこ", 2, 0),
+        ];
+        assert_eq!(passwords, expected.map(|(password, a, b)| (password.to_string(), a, b)));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::keypad::{self, Direction, Grid, Step};
+
+/// Buttons of an FCEUX gamepad, in the order they are logged.
+const BUTTONS: [char; 8] = ['R', 'L', 'D', 'U', 'T', 'S', 'B', 'A'];
+
+const A: u8 = 0x01;
+const UP: u8 = 0x10;
+const DOWN: u8 = 0x20;
+const LEFT: u8 = 0x40;
+const RIGHT: u8 = 0x80;
+
+/// MD5 checksum written when the ROM is not known, which FCEUX accepts with a warning.
+const UNKNOWN_ROM_CHECKSUM: &str = "base64:AAAAAAAAAAAAAAAAAAAAAA==";
+
+/// How many frames each button is held down, and released before the next one.
+pub(crate) struct Timing {
+    // Frames to wait before the first press, until the password screen accepts input
+    pub lead: usize,
+    pub hold: usize,
+    pub release: usize,
+}
+
+fn button(direction: Direction) -> u8 {
+    match direction {
+        Direction::Up => UP,
+        Direction::Down => DOWN,
+        Direction::Left => LEFT,
+        Direction::Right => RIGHT,
+    }
+}
+
+/// Lays out the presses of a plan frame by frame, as the buttons held in each frame.
+pub(crate) fn frames(steps: &[Step], timing: &Timing) -> Vec<u8> {
+    let presses = steps
+        .iter()
+        .flat_map(|step| step.moves.iter().map(|&direction| button(direction)).chain([A]));
+    let mut frames = vec![0; timing.lead];
+    for buttons in presses {
+        frames.extend(std::iter::repeat_n(buttons, timing.hold));
+        frames.extend(std::iter::repeat_n(0, timing.release));
+    }
+    frames
+}
+
+/// Plays frames back on the grid model, giving the password they enter.
+///
+/// Each button acts once when it is first held down, and entry stops at the end key.
+pub(crate) fn replay(grid: &Grid, frames: &[u8]) -> Result<String, String> {
+    let mut cursor = keypad::START;
+    let mut password = String::new();
+    let mut held = 0;
+    for &buttons in frames {
+        let pressed = buttons & !held;
+        held = buttons;
+        for direction in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+            if pressed & button(direction) != 0 {
+                cursor = grid.step(cursor, direction);
+            }
+        }
+        if pressed & A != 0 {
+            if cursor == grid.end {
+                return Ok(password);
+            }
+            password.extend(grid.rows[cursor.0].chars().nth(cursor.1));
+        }
+    }
+    Err(format!("The input never presses {}.", keypad::END_KEY))
+}
+
+/// Writes frames as an FCEUX movie with a gamepad in the first port.
+///
+/// The movie holds no savestate, so its input has to be played from the password screen,
+/// for example by pasting it after the frames that reach that screen.
+pub(crate) fn format_fm2(frames: &[u8], rom_filename: &str) -> String {
+    let mut lines = vec![
+        "version 3".to_string(),
+        "emuVersion 22020".to_string(),
+        "rerecordCount 0".to_string(),
+        "palFlag 0".to_string(),
+        format!("romFilename {}", rom_filename),
+        format!("romChecksum {}", UNKNOWN_ROM_CHECKSUM),
+        "guid 00000000-0000-0000-0000-000000000000".to_string(),
+        "fourscore 0".to_string(),
+        "microphone 0".to_string(),
+        "port0 1".to_string(),
+        "port1 0".to_string(),
+        "port2 0".to_string(),
+        "FDS 0".to_string(),
+        "NewPPU 0".to_string(),
+    ];
+    for &buttons in frames {
+        let gamepad = BUTTONS
+            .iter()
+            .enumerate()
+            .map(|(index, &name)| if buttons & (0x80 >> index) != 0 { name } else { '.' })
+            .collect::<String>();
+        lines.push(format!("|0|{}|||", gamepad));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dq1, dq2};
+
+    const TIMING: Timing = Timing { lead: 2, hold: 1, release: 1 };
+
+    fn round_trip(grid: &Grid, password: &str) -> Result<String, String> {
+        let steps = keypad::plan(grid, password)?;
+        replay(grid, &frames(&steps, &TIMING))
+    }
+
+    #[test]
+    fn replays_dq1_password() {
+        let password = "ろでどせつよづばぼぼきさたとねふやみろぐ";
+        assert_eq!(round_trip(&dq1::PASSWORD_GRID, password).as_deref(), Ok(password));
+    }
+
+    #[test]
+    fn replays_dq2_password() {
+        let password = "ならにはふせめめゆりろしたみろぎごず";
+        assert_eq!(round_trip(&dq2::PASSWORD_GRID, password).as_deref(), Ok(password));
+    }
+}
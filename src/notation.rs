@@ -0,0 +1,136 @@
+use clap::ValueEnum;
+
+use crate::input;
+
+/// How a password is written on the command line or printed.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Format {
+    /// Password characters, as shown in the game
+    Kana,
+    /// 6-bit indices into the game's character table, in decimal or `0x`-prefixed hex
+    Index,
+    /// Decrypted data bytes in hex, as packed by the game
+    Hex,
+}
+
+/// Converts a game's passwords to and from their character codes and packed bytes.
+pub(crate) struct Codec {
+    pub table: &'static [char; 64],
+    // Decrypts and packs a password into bytes, without checking its checksum
+    pub pack: fn(&str) -> Result<Vec<u8>, String>,
+    // Encodes packed bytes as a password, refusing a number of bytes no password packs into
+    pub unpack: fn(&[u8]) -> Result<String, String>,
+}
+
+/// Reads password arguments written in `format` as kana.
+///
+/// Indices and bytes may be separated by spaces or commas.
+pub(crate) fn to_kana(args: &[String], format: Format, codec: &Codec) -> Result<String, String> {
+    let tokens = args
+        .iter()
+        .flat_map(|arg| arg.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|token| !token.is_empty());
+    match format {
+        Format::Kana => Ok(input::join_password(args)),
+        Format::Index => tokens
+            .map(|token| {
+                let index = match token.strip_prefix("0x") {
+                    Some(hex) => u8::from_str_radix(hex, 16),
+                    None => token.parse::<u8>(),
+                };
+                index
+                    .ok()
+                    .and_then(|index| codec.table.get(index as usize))
+                    .copied()
+                    .ok_or_else(|| format!("Invalid character index (expected 0 to 63): {}", token))
+            })
+            .collect(),
+        Format::Hex => {
+            let digits =
+                tokens.map(|token| token.strip_prefix("0x").unwrap_or(token)).collect::<String>();
+            if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid hex bytes: {}", digits));
+            }
+            if digits.len() % 2 != 0 {
+                return Err(format!("Odd number of hex digits: {}", digits));
+            }
+            let bytes = (0..digits.len())
+                .step_by(2)
+                .map(|start| u8::from_str_radix(&digits[start..start + 2], 16).unwrap())
+                .collect::<Vec<_>>();
+            if bytes.is_empty() {
+                return Err("No bytes given.".to_string());
+            }
+            (codec.unpack)(&bytes)
+        }
+    }
+}
+
+/// Writes a password in `format`.
+pub(crate) fn from_kana(password: &str, format: Format, codec: &Codec) -> Result<String, String> {
+    match format {
+        Format::Kana => Ok(password.to_string()),
+        Format::Index => password
+            .chars()
+            .map(|c| {
+                codec
+                    .table
+                    .iter()
+                    .position(|&moji| moji == c)
+                    .map(|index| index.to_string())
+                    .ok_or_else(|| format!("Unsupported input character: {}", c))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|indices| indices.join(" ")),
+        Format::Hex => (codec.pack)(password).map(|bytes| {
+            bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ")
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dq1, dq2};
+
+    const DQ1: Codec = Codec {
+        table: &dq1::JUMON_MOJI_TABLE,
+        pack: dq1::packed_bytes,
+        unpack: dq1::password_from_bytes,
+    };
+    const DQ2: Codec = Codec {
+        table: &dq2::JUMON_MOJI_TABLE,
+        pack: dq2::packed_bytes,
+        unpack: dq2::password_from_bytes,
+    };
+
+    fn args(text: &str) -> Vec<String> {
+        text.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn converts_between_every_format_and_back() {
+        let dq1 = dq1::encode_game_data(&Default::default());
+        let dq2 = dq2::encode_game_data(&Default::default());
+        for (password, codec) in [(dq1.as_str(), &DQ1), (dq2.as_str(), &DQ2)] {
+            for format in [Format::Kana, Format::Index, Format::Hex] {
+                let written = from_kana(password, format, codec).unwrap();
+                assert_eq!(to_kana(&args(&written), format, codec).unwrap(), password);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_indices_in_decimal_or_hex_separated_by_commas() {
+        let expected = DQ1.table[..3].iter().collect::<String>();
+        assert_eq!(to_kana(&args("0,1 0x02"), Format::Index, &DQ1).unwrap(), expected);
+        assert!(to_kana(&args("64"), Format::Index, &DQ1).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_hex() {
+        for input in ["0", "zz", "", "00 00"] {
+            assert!(to_kana(&args(input), Format::Hex, &DQ1).is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use std::fs::File;
+
+use crate::input;
+
+/// Size of a tile of the NES background, in pixels.
+pub(crate) const TILE: usize = 8;
+
+/// Characters of the font sheet, in the order of its tiles. Kana with dakuten are drawn
+/// as the plain kana with its mark in the tile above.
+pub(crate) const FONT_ORDER: &str =
+    "あいうえおかきくけこさしすせそたちつてとなにぬねのはひふへほまみむめもやゆよらりるれろわ゛゜";
+
+/// Glyphs of the built-in font, one byte per row of pixels with the leftmost in the top bit.
+///
+/// They are drawn by hand after the games' kana rather than cut from the ROM, so a font
+/// sheet taken from the game itself reads real screenshots more reliably. Beyond the kana
+/// of the password screen, they cover the rest of the name characters and the digits and
+/// letters shown with a level and gold.
+const BUILTIN_GLYPHS: [(char, [u8; TILE]); 66] = [
+    ('あ', [0x10, 0x7E, 0x10, 0x3E, 0x52, 0x52, 0x26, 0x00]),
+    ('い', [0x00, 0x40, 0x44, 0x42, 0x42, 0x20, 0x00, 0x00]),
+    ('う', [0x38, 0x00, 0x78, 0x04, 0x04, 0x08, 0x30, 0x00]),
+    ('え', [0x38, 0x00, 0x7C, 0x08, 0x10, 0x28, 0x46, 0x00]),
+    ('お', [0x22, 0xFC, 0x20, 0x3C, 0x62, 0xA2, 0x6C, 0x00]),
+    ('か', [0x20, 0x7C, 0x25, 0x25, 0x44, 0x44, 0x8C, 0x00]),
+    ('き', [0x20, 0x7C, 0x10, 0x7C, 0x04, 0x3C, 0x40, 0x3C]),
+    ('く', [0x08, 0x10, 0x20, 0x40, 0x20, 0x10, 0x08, 0x00]),
+    ('け', [0x44, 0x5F, 0x44, 0x44, 0x44, 0x48, 0x50, 0x00]),
+    ('こ', [0x00, 0x7C, 0x00, 0x00, 0x40, 0x40, 0x3C, 0x00]),
+    ('さ', [0x10, 0x7E, 0x08, 0x3C, 0x40, 0x40, 0x3C, 0x00]),
+    ('し', [0x40, 0x40, 0x40, 0x40, 0x42, 0x44, 0x38, 0x00]),
+    ('す', [0x08, 0xFE, 0x08, 0x38, 0x28, 0x18, 0x10, 0x20]),
+    ('せ', [0x24, 0xFE, 0x24, 0x24, 0x2C, 0x20, 0x1E, 0x00]),
+    ('そ', [0x78, 0x10, 0x20, 0xFE, 0x10, 0x10, 0x0C, 0x00]),
+    ('た', [0x40, 0xF6, 0x40, 0x5C, 0x50, 0x90, 0x8E, 0x00]),
+    ('ち', [0x20, 0xFC, 0x20, 0x5C, 0x62, 0x02, 0x3C, 0x00]),
+    ('つ', [0x00, 0x00, 0x7C, 0x82, 0x02, 0x04, 0x38, 0x00]),
+    ('て', [0x00, 0xFE, 0x08, 0x10, 0x10, 0x10, 0x0C, 0x00]),
+    ('と', [0x40, 0x40, 0x4C, 0x70, 0x40, 0x40, 0x3C, 0x00]),
+    ('な', [0x40, 0xF4, 0x42, 0x88, 0x08, 0x3A, 0x3C, 0x00]),
+    ('に', [0x80, 0xBC, 0x80, 0x80, 0xA0, 0xA0, 0x9E, 0x00]),
+    ('ぬ', [0x24, 0x24, 0x7E, 0xAA, 0xAA, 0x4D, 0x0E, 0x00]),
+    ('ね', [0x40, 0x5C, 0x62, 0x42, 0xC6, 0x4A, 0x47, 0x00]),
+    ('の', [0x00, 0x38, 0x54, 0x92, 0xA2, 0xA4, 0x48, 0x00]),
+    ('は', [0x44, 0x5F, 0x44, 0x44, 0x4E, 0x55, 0x4C, 0x00]),
+    ('ひ', [0x00, 0xE4, 0x46, 0x82, 0x82, 0x84, 0x78, 0x00]),
+    ('ふ', [0x18, 0x04, 0x10, 0x10, 0x4A, 0x89, 0x30, 0x00]),
+    ('へ', [0x00, 0x10, 0x28, 0x44, 0x82, 0x01, 0x00, 0x00]),
+    ('ほ', [0x5F, 0x44, 0x5F, 0x44, 0x4E, 0x55, 0x4C, 0x00]),
+    ('ま', [0x10, 0x7C, 0x10, 0x7C, 0x10, 0x38, 0x56, 0x20]),
+    ('み', [0x70, 0x10, 0x24, 0x7C, 0xA5, 0x44, 0x08, 0x00]),
+    ('む', [0x20, 0xFA, 0x21, 0x60, 0xA0, 0x62, 0x3C, 0x00]),
+    ('め', [0x44, 0x5C, 0x6A, 0xB2, 0xA2, 0x44, 0x18, 0x00]),
+    ('も', [0x10, 0x78, 0x10, 0x78, 0x12, 0x12, 0x0C, 0x00]),
+    ('や', [0x40, 0x4C, 0xF2, 0x44, 0x20, 0x20, 0x10, 0x00]),
+    ('ゆ', [0x20, 0xBC, 0xA4, 0xA4, 0xBC, 0x20, 0x40, 0x00]),
+    ('よ', [0x08, 0x0E, 0x08, 0x08, 0x78, 0x8A, 0x72, 0x00]),
+    ('ら', [0x30, 0x08, 0x40, 0x5C, 0x62, 0x02, 0x38, 0x00]),
+    ('り', [0x44, 0x44, 0x44, 0x44, 0x24, 0x04, 0x18, 0x00]),
+    ('る', [0x7C, 0x08, 0x10, 0x3C, 0x42, 0x1A, 0x2E, 0x18]),
+    ('れ', [0x40, 0x48, 0x68, 0x48, 0xC8, 0x49, 0x46, 0x00]),
+    ('ろ', [0x7C, 0x08, 0x10, 0x3C, 0x42, 0x02, 0x38, 0x00]),
+    ('わ', [0x40, 0x58, 0x64, 0x42, 0xC2, 0x44, 0x58, 0x00]),
+    ('゛', [0x00, 0x00, 0x00, 0x00, 0x05, 0x05, 0x00, 0x00]),
+    ('゜', [0x00, 0x00, 0x00, 0x06, 0x09, 0x09, 0x06, 0x00]),
+    ('を', [0x20, 0xFC, 0x24, 0x58, 0x60, 0x9C, 0x10, 0x0E]),
+    ('ん', [0x10, 0x10, 0x20, 0x30, 0x48, 0x49, 0x86, 0x00]),
+    ('っ', [0x00, 0x00, 0x00, 0x38, 0x44, 0x04, 0x18, 0x00]),
+    ('ゃ', [0x00, 0x00, 0x40, 0x58, 0xE4, 0x48, 0x20, 0x00]),
+    ('ゅ', [0x00, 0x00, 0x20, 0xB8, 0xA8, 0xB8, 0x20, 0x00]),
+    ('ょ', [0x00, 0x00, 0x08, 0x0C, 0x08, 0x70, 0x8C, 0x00]),
+    ('ー', [0x00, 0x00, 0x00, 0xFE, 0x00, 0x00, 0x00, 0x00]),
+    ('0', [0x38, 0x44, 0x82, 0x82, 0x82, 0x44, 0x38, 0x00]),
+    ('1', [0x10, 0x30, 0x10, 0x10, 0x10, 0x10, 0x7C, 0x00]),
+    ('2', [0x7C, 0x82, 0x06, 0x1C, 0x70, 0xC0, 0xFE, 0x00]),
+    ('3', [0xFE, 0x0C, 0x18, 0x0C, 0x02, 0x82, 0x7C, 0x00]),
+    ('4', [0x18, 0x38, 0x50, 0x90, 0xFE, 0x10, 0x10, 0x00]),
+    ('5', [0xFC, 0x80, 0xFC, 0x02, 0x02, 0x82, 0x7C, 0x00]),
+    ('6', [0x38, 0x40, 0x80, 0xFC, 0x82, 0x82, 0x7C, 0x00]),
+    ('7', [0xFE, 0x82, 0x04, 0x08, 0x10, 0x10, 0x10, 0x00]),
+    ('8', [0x78, 0x84, 0x84, 0x7C, 0x82, 0x82, 0x7C, 0x00]),
+    ('9', [0x7C, 0x82, 0x82, 0x7E, 0x02, 0x04, 0x78, 0x00]),
+    ('L', [0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x7E, 0x00]),
+    ('V', [0x82, 0x82, 0x82, 0x44, 0x44, 0x28, 0x10, 0x00]),
+    ('G', [0x3C, 0x42, 0x80, 0x8E, 0x82, 0x42, 0x3E, 0x00]),
+];
+
+/// Least share of matching pixels for a tile to be read as a character.
+const MIN_SIMILARITY: f64 = 0.9;
+
+/// Marks a tile that could not be read with confidence, as taken by `complete`.
+pub(crate) const UNSURE: char = '?';
+
+/// A screenshot reduced to ink and background pixels, one per pixel of the NES screen.
+pub(crate) struct Bitmap {
+    width: usize,
+    height: usize,
+    ink: Vec<bool>,
+}
+
+/// The pixels of a tile, row by row, with `true` for ink.
+pub(crate) type Tile = [bool; TILE * TILE];
+
+impl Bitmap {
+    /// Reads a PNG image, treating light pixels as ink as on the password screen, and
+    /// scales it back down to NES pixels by `scale` across and down.
+    ///
+    /// Without a scale, it is worked out from the image, so screenshots scaled up by a
+    /// whole factor read as they are.
+    pub(crate) fn read_png(path: &str, scale: Option<(f64, f64)>) -> Result<Bitmap, String> {
+        let error = |err: &dyn std::fmt::Display| format!("Failed to read image {}: {}", path, err);
+        let file = File::open(path).map_err(|err| error(&err))?;
+        let mut decoder = png::Decoder::new(file);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|err| error(&err))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|err| error(&err))?;
+
+        let samples = info.color_type.samples();
+        let (width, height) = (info.width as usize, info.height as usize);
+        let luma = |x: usize, y: usize| {
+            let pixel = &buffer[y * info.line_size + x * samples..][..samples];
+            match info.color_type {
+                png::ColorType::Rgb | png::ColorType::Rgba => {
+                    (pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000
+                }
+                _ => pixel[0] as u32,
+            }
+        };
+        let image = Bitmap {
+            width,
+            height,
+            ink: (0..width * height)
+                .map(|index| luma(index % width, index / width) >= 128)
+                .collect(),
+        };
+
+        let (scale_x, scale_y) = scale.unwrap_or_else(|| image.scale());
+        let (width, height) =
+            ((width as f64 / scale_x) as usize, (height as f64 / scale_y) as usize);
+        let mut ink = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                // Sample the middle of each scaled pixel, away from any smoothing at its edges
+                let (x, y) =
+                    (((x as f64 + 0.5) * scale_x) as usize, ((y as f64 + 0.5) * scale_y) as usize);
+                ink.push(image.ink[y.min(image.height - 1) * image.width + x.min(image.width - 1)]);
+            }
+        }
+        Ok(Bitmap { width, height, ink })
+    }
+
+    /// Works out how many pixels of the image each NES pixel spans across and down, as the
+    /// greatest common divisor of the runs of ink and background.
+    ///
+    /// Runs cut off by the edges of the image are left out, since a crop may split a pixel.
+    fn scale(&self) -> (f64, f64) {
+        let divisor = |lines: &mut dyn Iterator<Item = Vec<bool>>| {
+            let mut divisor = 0;
+            for line in lines {
+                let runs = line.chunk_by(|a, b| a == b).map(<[bool]>::len).collect::<Vec<_>>();
+                for &run in runs.iter().skip(1).take(runs.len().saturating_sub(2)) {
+                    divisor = gcd(divisor, run);
+                }
+            }
+            divisor.max(1) as f64
+        };
+        let rows = &mut self.ink.chunks(self.width).map(<[bool]>::to_vec);
+        let columns = &mut (0..self.width)
+            .map(|x| (0..self.height).map(|y| self.ink[y * self.width + x]).collect::<Vec<_>>());
+        (divisor(rows), divisor(columns))
+    }
+
+    /// Cuts out a region of the bitmap, given as `x,y,width,height` in screen pixels.
+    ///
+    /// The region is clipped to the bitmap, and must start inside it.
+    pub(crate) fn crop(
+        &self,
+        (x, y, width, height): (usize, usize, usize, usize),
+    ) -> Result<Bitmap, String> {
+        if x >= self.width || y >= self.height {
+            return Err(format!(
+                "The region at {},{} lies outside the screen ({}×{} pixels).",
+                x, y, self.width, self.height
+            ));
+        }
+        let (width, height) = (width.min(self.width - x), height.min(self.height - y));
+        let mut ink = Vec::with_capacity(width * height);
+        for row in y..y + height {
+            ink.extend_from_slice(&self.ink[row * self.width + x..][..width]);
+        }
+        Ok(Bitmap { width, height, ink })
+    }
+
+    fn tile(&self, left: usize, top: usize) -> Tile {
+        let mut tile = [false; TILE * TILE];
+        for y in 0..TILE {
+            for x in 0..TILE {
+                tile[y * TILE + x] = self.ink[(top + y) * self.width + left + x];
+            }
+        }
+        tile
+    }
+
+    /// Splits the bitmap into tiles from `offset`, row by row.
+    fn tiles(&self, (dx, dy): (usize, usize)) -> Vec<Vec<Tile>> {
+        (0..self.height.saturating_sub(dy) / TILE)
+            .map(|row| {
+                (0..self.width.saturating_sub(dx) / TILE)
+                    .map(|column| self.tile(dx + column * TILE, dy + row * TILE))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Templates of the characters of a game's font.
+pub(crate) struct Font {
+    templates: Vec<(char, Tile)>,
+}
+
+impl Font {
+    /// Gives the built-in font, used when no font sheet is given.
+    pub(crate) fn builtin() -> Font {
+        let templates = BUILTIN_GLYPHS
+            .iter()
+            .map(|&(c, rows)| {
+                let mut tile = [false; TILE * TILE];
+                for (index, pixel) in tile.iter_mut().enumerate() {
+                    *pixel = rows[index / TILE] & (0x80 >> (index % TILE)) != 0;
+                }
+                (c, tile)
+            })
+            .collect();
+        Font { templates }
+    }
+
+    /// Reads a font sheet: a PNG image of 8×8 tiles in the order of `FONT_ORDER`,
+    /// left to right and top to bottom, with light characters on a dark background.
+    pub(crate) fn read_png(path: &str) -> Result<Font, String> {
+        let sheet = Bitmap::read_png(path, None)?;
+        let tiles = sheet.tiles((0, 0)).concat();
+        if tiles.len() < FONT_ORDER.chars().count() {
+            return Err(format!(
+                "The font sheet {} has {} tiles; expected {} ({}).",
+                path,
+                tiles.len(),
+                FONT_ORDER.chars().count(),
+                FONT_ORDER
+            ));
+        }
+        Ok(Font { templates: FONT_ORDER.chars().zip(tiles).collect() })
+    }
+
+    /// Gives the tile of a character of the font, drawing full-width digits as the digits.
+    pub(crate) fn glyph(&self, c: char) -> Option<&Tile> {
+        let c = match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32)?,
+            _ => c,
+        };
+        self.templates.iter().find(|(template, _)| *template == c).map(|(_, tile)| tile)
+    }
+
+    /// Finds the character of the password screen a tile most resembles, with the share
+    /// of pixels that match.
+    fn best_match(&self, tile: &Tile) -> (char, f64) {
+        self.templates
+            .iter()
+            .filter(|(c, _)| FONT_ORDER.contains(*c))
+            .map(|(c, template)| {
+                let matching = tile.iter().zip(template).filter(|(a, b)| a == b).count();
+                (*c, matching as f64 / tile.len() as f64)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+    }
+}
+
+/// Gives the share of the ink of two tiles that both have.
+fn ink_overlap(a: &Tile, b: &Tile) -> f64 {
+    let both = a.iter().zip(b).filter(|(a, b)| **a && **b).count();
+    let either = a.iter().zip(b).filter(|(a, b)| **a || **b).count();
+    both as f64 / either.max(1) as f64
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Reads the characters of a screenshot of the password screen, top to bottom and left
+/// to right, with `UNSURE` for each tile that matches no character of the font well.
+///
+/// The tile grid is aligned where the tiles match best, so the screenshot may be cropped
+/// at any position. `tables` give the characters that kana and a mark above combine into.
+pub(crate) fn read_password(bitmap: &Bitmap, font: &Font, tables: &[&[char; 64]]) -> String {
+    let offsets = (0..TILE).flat_map(|dy| (0..TILE).map(move |dx| (dx, dy)));
+    let matches = offsets.map(|offset| {
+        let rows = bitmap
+            .tiles(offset)
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|tile| tile.contains(&true).then(|| font.best_match(tile)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        // Pieces of neighbouring tiles can come close to sparse characters pixel for pixel,
+        // so alignments are compared by how much of the ink the matched characters share
+        let overlap = rows
+            .iter()
+            .zip(bitmap.tiles(offset))
+            .flat_map(|(row, tiles)| row.iter().zip(tiles))
+            .filter_map(|(cell, tile)| match *cell {
+                Some((c, similarity)) if similarity >= MIN_SIMILARITY => {
+                    font.glyph(c).map(|template| ink_overlap(&tile, template))
+                }
+                _ => None,
+            })
+            .sum::<f64>();
+        (overlap, rows)
+    });
+    let (_, rows) = matches.max_by(|a, b| a.0.total_cmp(&b.0)).unwrap_or_default();
+
+    // Rows and columns where no character reads well hold the window's border
+    let confident = |cell: &Option<(char, f64)>| {
+        cell.is_some_and(|(_, similarity)| similarity >= MIN_SIMILARITY)
+    };
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let columns = (0..width)
+        .map(|column| rows.iter().any(|row| row.get(column).is_some_and(confident)))
+        .collect::<Vec<_>>();
+
+    let mut password = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        if !row.iter().any(confident) {
+            continue;
+        }
+        for (column, cell) in row.iter().enumerate() {
+            let Some((c, similarity)) = *cell else {
+                continue;
+            };
+            if !columns[column] {
+                continue;
+            }
+            if c == '゛' || c == '゜' {
+                // Marks are read along with the kana below them
+                continue;
+            }
+            if similarity < MIN_SIMILARITY {
+                password.push(UNSURE);
+                continue;
+            }
+            let mark = index
+                .checked_sub(1)
+                .and_then(|above| rows[above][column])
+                .filter(|&(mark, similarity)| {
+                    (mark == '゛' || mark == '゜') && similarity >= MIN_SIMILARITY
+                })
+                .map(|(mark, _)| mark);
+            password.push(match mark {
+                Some(mark) => tables
+                    .iter()
+                    .flat_map(|table| table.iter().copied())
+                    .find(|&kana| {
+                        input::split_dakuten(&kana.to_string()) == format!("{}{}", c, mark)
+                    })
+                    .unwrap_or(UNSURE),
+                None => c,
+            });
+        }
+    }
+    password
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dq1;
+
+    /// The password drawn in the fixtures by the render command, in the built-in font.
+    const PASSWORD: &str = "つだけとひぐめとねくふでべさつにへあほれ";
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    fn read(bitmap: &Bitmap) -> String {
+        read_password(bitmap, &Font::builtin(), &[&dq1::JUMON_MOJI_TABLE])
+    }
+
+    #[test]
+    fn reads_screenshot_at_detected_scale() {
+        let bitmap = Bitmap::read_png(&fixture("dq1_scale3.png"), None).unwrap();
+        assert_eq!((bitmap.width, bitmap.height), (120, 64));
+        assert_eq!(read(&bitmap), PASSWORD);
+    }
+
+    #[test]
+    fn reads_screenshot_at_given_scale() {
+        // Stretched to an 8:7 pixel aspect ratio, which no whole factor undoes
+        let bitmap = Bitmap::read_png(&fixture("dq1_8x7.png"), Some((16.0 / 7.0, 2.0))).unwrap();
+        assert_eq!(read(&bitmap), PASSWORD);
+    }
+
+    #[test]
+    fn reads_cropped_screenshot() {
+        let bitmap = Bitmap::read_png(&fixture("dq1_scale3.png"), None).unwrap();
+        let bitmap = bitmap.crop((3, 3, 200, 200)).unwrap();
+        assert_eq!((bitmap.width, bitmap.height), (117, 61));
+        assert_eq!(read(&bitmap), PASSWORD);
+        assert!(bitmap.crop((1000, 235, 5, 5)).is_err());
+    }
+}
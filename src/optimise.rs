@@ -0,0 +1,227 @@
+use prettytable::{row, Table};
+
+use crate::rule::{self, Severity, Violation};
+
+/// Fields set by `--set`, as `(field, value)` pairs.
+type Assignments = [(String, String)];
+
+/// The value reached for a field being maximised, and the rules that stopped it going higher.
+pub(crate) struct Outcome {
+    pub field: String,
+    pub value: u32,
+    pub max: u32,
+    pub limits: Vec<&'static str>,
+}
+
+/// The best game data found, with the `--set` fields that held the objectives back.
+pub(crate) struct Optimum<T> {
+    pub data: T,
+    pub outcomes: Vec<Outcome>,
+    pub binding: Vec<String>,
+}
+
+/// Hooks into the game data being optimised.
+pub(crate) struct Hooks<T> {
+    pub from_assignments: fn(&Assignments) -> Result<T, String>,
+    pub set_field: fn(&mut T, &str, &str) -> Result<(), String>,
+    pub validate: fn(&T) -> Vec<Violation>,
+    // Numeric fields that can be maximised, with the largest value each can hold
+    pub objectives: &'static [(&'static str, u32)],
+}
+
+/// Raises each field of `fields` in turn to the highest value that breaks no rule,
+/// keeping the fields set by `assignments`.
+///
+/// A `--set` field is binding if dropping it would let the objectives go higher.
+pub(crate) fn optimise<T: Clone>(
+    hooks: &Hooks<T>,
+    assignments: &[(String, String)],
+    fields: &[String],
+) -> Result<Optimum<T>, String> {
+    let mut objectives = vec![];
+    for field in fields {
+        let &(name, max) =
+            hooks.objectives.iter().find(|(name, _)| name == field).ok_or_else(|| {
+                format!("Cannot maximise {}; expected one of: {}", field, names(hooks))
+            })?;
+        if assignments.iter().any(|(assigned, _)| assigned == name) {
+            return Err(format!("{} cannot be both set and maximised.", name));
+        }
+        objectives.push((name, max));
+    }
+
+    let (data, outcomes) = maximise(hooks, assignments, &objectives)?;
+    let values = outcomes.iter().map(|outcome| outcome.value).collect::<Vec<_>>();
+    let binding = assignments
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| {
+            let mut relaxed = assignments.to_vec();
+            relaxed.remove(index);
+            maximise(hooks, &relaxed, &objectives).is_ok_and(|(_, relaxed)| {
+                relaxed.iter().map(|outcome| outcome.value).collect::<Vec<_>>() > values
+            })
+        })
+        .map(|(_, (field, _))| field.clone())
+        .collect();
+
+    Ok(Optimum { data, outcomes, binding })
+}
+
+fn names<T>(hooks: &Hooks<T>) -> String {
+    hooks.objectives.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+}
+
+/// Raises each objective in turn to its highest value that breaks no rule.
+///
+/// Every rule of either game that is an error caps a quantity from above, so a value that
+/// breaks no rule is followed only by lower ones that break none either. Each objective
+/// can therefore be found by bisection, once the data is known to be valid at zero.
+fn maximise<T: Clone>(
+    hooks: &Hooks<T>,
+    assignments: &[(String, String)],
+    objectives: &[(&str, u32)],
+) -> Result<(T, Vec<Outcome>), String> {
+    let errors = |data: &T| {
+        (hooks.validate)(data)
+            .into_iter()
+            .filter(|violation| violation.severity == Severity::Error)
+            .collect::<Vec<_>>()
+    };
+    let with_value = |data: &T, field: &str, value: u32| {
+        let mut data = data.clone();
+        (hooks.set_field)(&mut data, field, &value.to_string()).map(|_| data)
+    };
+
+    // Check once that the constraints can be met at all, with every objective at zero
+    let mut data = (hooks.from_assignments)(assignments)?;
+    for &(field, _) in objectives {
+        data = with_value(&data, field, 0)?;
+    }
+    let violations = (hooks.validate)(&data);
+    if rule::has_errors(&violations) {
+        return Err(format!(
+            "The constraints break the rules of the game:\n{}",
+            rule::format_violations(&violations)
+        ));
+    }
+
+    let mut outcomes = vec![];
+    for &(field, max) in objectives {
+        // The highest value known to break no rule, and the lowest known to break one
+        let (mut valid, mut invalid) = (0, max as u64 + 1);
+        while invalid - valid > 1 {
+            let middle = valid + (invalid - valid) / 2;
+            match errors(&with_value(&data, field, middle as u32)?).is_empty() {
+                true => valid = middle,
+                false => invalid = middle,
+            }
+        }
+        let value = valid as u32;
+
+        // The rules broken by the next value up are the ones holding the field back
+        let limits = if value == max {
+            vec![]
+        } else {
+            errors(&with_value(&data, field, value + 1)?)
+                .into_iter()
+                .map(|violation| violation.rule)
+                .collect()
+        };
+        outcomes.push(Outcome { field: field.to_string(), value, max, limits });
+        data = with_value(&data, field, value)?;
+    }
+    Ok((data, outcomes))
+}
+
+/// Formats the outcome of each objective as a table.
+pub(crate) fn tabulate_outcomes(outcomes: &[Outcome]) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["Field", "Value", "Largest Encodable", "Limited By"]);
+    for outcome in outcomes {
+        let limits = match outcome.limits.is_empty() {
+            true => "(field size)".to_string(),
+            false => outcome.limits.join(", "),
+        };
+        table.add_row(row![outcome.field, outcome.value, outcome.max, limits]);
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dq1;
+    use crate::rule::Rule;
+
+    /// Two counts that may add up to at most 10.
+    type Pair = [u32; 2];
+
+    const RULES: [Rule<Pair>; 1] = [Rule {
+        name: "sum-cap",
+        severity: Severity::Error,
+        check: |pair| (pair[0] + pair[1] > 10).then(|| "More than 10 in all".to_string()),
+    }];
+
+    fn set_field(pair: &mut Pair, field: &str, value: &str) -> Result<(), String> {
+        let index = ["a", "b"].iter().position(|&name| name == field).ok_or("Unknown field")?;
+        pair[index] = value.parse().map_err(|_| "Invalid value")?;
+        Ok(())
+    }
+
+    const PAIR: Hooks<Pair> = Hooks {
+        from_assignments: |assignments| {
+            let mut pair = [0; 2];
+            for (field, value) in assignments {
+                set_field(&mut pair, field, value)?;
+            }
+            Ok(pair)
+        },
+        set_field,
+        validate: |pair| rule::validate(&RULES, pair),
+        objectives: &[("a", 15), ("b", 15)],
+    };
+
+    fn assignments(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|&(field, value)| (field.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn raises_objectives_in_turn_and_names_binding_fields() {
+        let optimum = optimise(&PAIR, &assignments(&[("a", "4")]), &["b".to_string()]).unwrap();
+        assert_eq!(optimum.data, [4, 6]);
+        assert_eq!(optimum.outcomes[0].limits, ["sum-cap"]);
+        assert_eq!(optimum.binding, ["a"]);
+
+        // The first objective takes all it can before the second is raised
+        let fields = ["b".to_string(), "a".to_string()];
+        let optimum = optimise(&PAIR, &[], &fields).unwrap();
+        assert_eq!(optimum.data, [0, 10]);
+        assert!(optimum.binding.is_empty());
+    }
+
+    #[test]
+    fn rejects_constraints_that_break_the_rules_and_set_objectives() {
+        assert!(optimise(&PAIR, &assignments(&[("a", "11")]), &["b".to_string()]).is_err());
+        assert!(optimise(&PAIR, &assignments(&[("b", "1")]), &["b".to_string()]).is_err());
+        assert!(optimise(&PAIR, &[], &["c".to_string()]).is_err());
+    }
+
+    #[test]
+    fn caps_dq1_herbs_at_what_can_be_carried() {
+        let hooks = Hooks {
+            from_assignments: dq1::GameData::from_assignments,
+            set_field: dq1::GameData::set_field,
+            validate: dq1::GameData::validate,
+            objectives: &dq1::OBJECTIVES,
+        };
+        let fields = ["herbs", "gold"].map(str::to_string);
+        let optimum = optimise(&hooks, &[], &fields).unwrap();
+        assert_eq!(optimum.data.herbs, 6);
+        assert_eq!(optimum.outcomes[0].limits, ["too-many-herbs"]);
+        assert_eq!(
+            (optimum.outcomes[1].value, optimum.outcomes[1].limits.len()),
+            (u16::MAX as u32, 0)
+        );
+    }
+}
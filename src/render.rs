@@ -0,0 +1,210 @@
+use crate::input;
+use crate::ocr::{Font, TILE};
+
+/// Size of an NES pixel in an SVG image, in SVG pixels.
+const SVG_PIXEL: usize = 4;
+
+/// Tiles of black margin around the password, inside which the border is drawn.
+const MARGIN: usize = 2;
+
+/// A character of the password in the grid of tiles, with its mark in the tile above.
+struct Placed {
+    row: usize,
+    column: usize,
+    kana: char,
+    mark: Option<char>,
+}
+
+/// Places the characters of a password in rows of groups, separated by an empty tile,
+/// leaving a row for marks above each row of kana. Gives the size of the grid in tiles.
+fn place(password: &str, groups: &[usize]) -> (Vec<Placed>, usize, usize) {
+    let row_length: usize = groups.iter().sum();
+    let mut placed = vec![];
+    let (mut columns, mut rows) = (0, 0);
+    for (index, c) in password.chars().enumerate() {
+        let (line, mut position) = (index / row_length, index % row_length);
+        let mut column = 0;
+        for group in groups {
+            if position < *group {
+                column += position;
+                break;
+            }
+            position -= group;
+            column += group + 1;
+        }
+        let split = input::split_dakuten(&c.to_string()).chars().collect::<Vec<_>>();
+        let (row, column) = (MARGIN + line * 2 + 1, MARGIN + column);
+        placed.push(Placed { row, column, kana: split[0], mark: split.get(1).copied() });
+        columns = columns.max(column + 1 + MARGIN);
+        rows = rows.max(row + 1 + MARGIN);
+    }
+    (placed, columns, rows)
+}
+
+/// Places lines of text in the rows under a grid of `rows` tiles, each with a row for
+/// marks above it, leaving spaces blank. Gives the size of the grid with the text.
+fn place_summary(summary: &[String], columns: usize, rows: usize) -> (Vec<Placed>, usize, usize) {
+    let mut placed = vec![];
+    let mut columns = columns;
+    for (index, line) in summary.iter().enumerate() {
+        let row = rows - MARGIN + index * 2 + 1;
+        let split = input::split_dakuten(line);
+        let mut column = MARGIN;
+        let mut characters = split.chars().peekable();
+        while let Some(kana) = characters.next() {
+            let mark = characters.next_if(|&c| c == '゛' || c == '゜');
+            if kana != ' ' && kana != '　' {
+                placed.push(Placed { row, column, kana, mark });
+            }
+            column += 1;
+        }
+        columns = columns.max(column + MARGIN);
+    }
+    (placed, columns, rows + summary.len() * 2)
+}
+
+/// Draws a password as pixels of the game's password window, one per NES pixel, with any
+/// lines of `summary` written underneath in the tiles of a font. Gives the pixels with
+/// their width and height.
+fn draw(
+    password: &str,
+    groups: &[usize],
+    summary: &[String],
+    font: &Font,
+) -> Result<(Vec<bool>, usize, usize), String> {
+    let (mut placed, columns, rows) = place(password, groups);
+    let (lines, columns, rows) = place_summary(summary, columns, rows);
+    placed.extend(lines);
+    let (width, height) = (columns * TILE, rows * TILE);
+    let mut pixels = vec![false; width * height];
+
+    // A white border two pixels wide, midway through the margin
+    let inset = TILE * MARGIN / 2;
+    for y in inset..height - inset {
+        for x in inset..width - inset {
+            let edge =
+                x < inset + 2 || x >= width - inset - 2 || y < inset + 2 || y >= height - inset - 2;
+            if edge {
+                pixels[y * width + x] = true;
+            }
+        }
+    }
+
+    let mut draw = |row: usize, column: usize, c: char| -> Result<(), String> {
+        let glyph = font.glyph(c).ok_or_else(|| format!("The font has no tile for {}.", c))?;
+        for (index, &ink) in glyph.iter().enumerate() {
+            if ink {
+                let (x, y) = (column * TILE + index % TILE, row * TILE + index / TILE);
+                pixels[y * width + x] = true;
+            }
+        }
+        Ok(())
+    };
+    for character in &placed {
+        draw(character.row, character.column, character.kana)?;
+        if let Some(mark) = character.mark {
+            draw(character.row - 1, character.column, mark)?;
+        }
+    }
+    Ok((pixels, width, height))
+}
+
+/// Draws a password as an SVG image of the game's password window, with any lines of
+/// `summary` written underneath, in the tiles of a font.
+///
+/// Each run of white pixels in a line is a rectangle, so the image matches the PNG.
+pub(crate) fn render_svg(
+    password: &str,
+    groups: &[usize],
+    summary: &[String],
+    font: &Font,
+) -> Result<String, String> {
+    let (pixels, width, height) = draw(password, groups, summary, font)?;
+    let mut lines = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {2} {3}" shape-rendering="crispEdges">"#,
+            width * SVG_PIXEL,
+            height * SVG_PIXEL,
+            width,
+            height
+        ),
+        r#"<rect width="100%" height="100%" fill="black"/>"#.to_string(),
+        r#"<g fill="white">"#.to_string(),
+    ];
+    for (y, line) in pixels.chunks(width).enumerate() {
+        let mut x = 0;
+        for run in line.chunk_by(|a, b| a == b) {
+            if run[0] {
+                lines.push(format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="1"/>"#,
+                    x,
+                    y,
+                    run.len()
+                ));
+            }
+            x += run.len();
+        }
+    }
+    lines.push("</g>".to_string());
+    lines.push("</svg>".to_string());
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+/// Draws a password as a PNG image of the game's password window, with any lines of
+/// `summary` written underneath, in the tiles of a font, each pixel scaled up `scale` times.
+pub(crate) fn render_png(
+    password: &str,
+    groups: &[usize],
+    summary: &[String],
+    font: &Font,
+    scale: usize,
+) -> Result<Vec<u8>, String> {
+    let (pixels, width, height) = draw(password, groups, summary, font)?;
+    let scaled = pixels
+        .chunks(width)
+        .flat_map(|line| {
+            let line = line
+                .iter()
+                .flat_map(|&pixel| std::iter::repeat_n(if pixel { 0xFF } else { 0 }, scale));
+            std::iter::repeat_n(line.collect::<Vec<u8>>(), scale).flatten()
+        })
+        .collect::<Vec<_>>();
+    let mut image = vec![];
+    let mut encoder =
+        png::Encoder::new(&mut image, (width * scale) as u32, (height * scale) as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let error = |err: png::EncodingError| format!("Failed to encode image: {}", err);
+    let mut writer = encoder.write_header().map_err(error)?;
+    writer.write_image_data(&scaled).map_err(error)?;
+    writer.finish().map_err(error)?;
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dq1;
+
+    #[test]
+    fn svg_draws_the_same_pixels_as_png() {
+        let (password, summary) =
+            ("つだけとひぐめとねくふでべさつにへあほれ", ["ゆうてい　LV 8　500G".to_string()]);
+        let font = Font::builtin();
+        let svg = render_svg(password, &dq1::PASSWORD_LAYOUT, &summary, &font).unwrap();
+        let (expected, width, height) =
+            draw(password, &dq1::PASSWORD_LAYOUT, &summary, &font).unwrap();
+
+        let mut pixels = vec![false; width * height];
+        let attribute = |element: &str, name: &str| -> usize {
+            let value = element.split(&format!(r#" {}=""#, name)).nth(1).unwrap();
+            value[..value.find('"').unwrap()].parse().unwrap()
+        };
+        for element in svg.lines().filter(|line| line.starts_with("<rect x=")) {
+            let (x, y) = (attribute(element, "x"), attribute(element, "y"));
+            pixels[y * width + x..][..attribute(element, "width")].fill(true);
+        }
+        assert!(pixels == expected);
+    }
+}
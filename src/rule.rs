@@ -0,0 +1,55 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Severity {
+    // The state is unusual, but can occur in the game
+    Warning,
+    // The state cannot occur in the game
+    Error,
+}
+
+/// A semantic rule that decoded game data should follow.
+pub(crate) struct Rule<T> {
+    pub name: &'static str,
+    pub severity: Severity,
+    // Returns an explanation if the data breaks the rule
+    pub check: fn(&T) -> Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Violation {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub explanation: String,
+}
+
+/// Checks the data against every rule, returning the rules it breaks.
+pub(crate) fn validate<T>(rules: &[Rule<T>], data: &T) -> Vec<Violation> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            (rule.check)(data).map(|explanation| Violation {
+                rule: rule.name,
+                severity: rule.severity,
+                explanation,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn has_errors(violations: &[Violation]) -> bool {
+    violations.iter().any(|violation| violation.severity == Severity::Error)
+}
+
+/// Formats violations for display, one per line.
+pub(crate) fn format_violations(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(|violation| {
+            let severity = match violation.severity {
+                Severity::Warning => "Warning",
+                Severity::Error => "Error",
+            };
+            format!("{} ({}): {}", severity, violation.rule, violation.explanation)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
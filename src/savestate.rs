@@ -0,0 +1,112 @@
+use std::fs;
+
+/// Size of the NES work RAM, which a raw dump holds in full.
+pub(crate) const RAM_SIZE: usize = 0x800;
+
+/// The work RAM of the console, indexed by CPU address.
+pub(crate) type Ram = [u8; RAM_SIZE];
+
+/// Magic number at the start of an FCEUX save state.
+const FCEUX_MAGIC: &[u8; 4] = b"FCSX";
+
+/// Size of the header of an FCEUX save state, before the (possibly compressed) sections.
+const FCEUX_HEADER_SIZE: usize = 16;
+
+/// Compressed size written in the header when the sections are stored uncompressed.
+const FCEUX_UNCOMPRESSED: u32 = u32::MAX;
+
+/// Section of an FCEUX save state that holds the CPU registers and the work RAM.
+const FCEUX_CPU_SECTION: u8 = 1;
+
+/// Name of the chunk of the CPU section that holds the work RAM.
+const FCEUX_RAM_CHUNK: &[u8; 4] = b"RAM\0";
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes.get(offset..offset + 4).map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+}
+
+/// Reads the work RAM from a raw 2 KB dump or an FCEUX save state (.fc0 to .fc9).
+pub(crate) fn read_ram(path: &str) -> Result<Ram, String> {
+    let bytes = fs::read(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+    if bytes.starts_with(FCEUX_MAGIC) {
+        return fceux_ram(&bytes).map_err(|err| format!("{}: {}", path, err));
+    }
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        format!(
+            "{} is neither an FCEUX save state nor a RAM dump of {} bytes ({} bytes).",
+            path,
+            RAM_SIZE,
+            bytes.len()
+        )
+    })
+}
+
+/// Finds the work RAM in an FCEUX save state.
+///
+/// After the header, the state is a run of sections, each a type byte and a 32-bit size
+/// followed by chunks of a 4-byte name, a 32-bit size and the data. The sections may be
+/// compressed as a whole with zlib.
+fn fceux_ram(state: &[u8]) -> Result<Ram, String> {
+    let truncated = || "The save state is truncated.".to_string();
+    let compressed_size = read_u32(state, 12).ok_or_else(truncated)?;
+    let body = &state[FCEUX_HEADER_SIZE..];
+    let sections = match compressed_size {
+        FCEUX_UNCOMPRESSED => body.to_vec(),
+        size => {
+            let compressed = body.get(..size as usize).ok_or_else(truncated)?;
+            miniz_oxide::inflate::decompress_to_vec_zlib(compressed)
+                .map_err(|err| format!("Failed to decompress the save state: {}", err))?
+        }
+    };
+
+    let mut offset = 0;
+    while offset < sections.len() {
+        let kind = sections[offset];
+        let size = read_u32(&sections, offset + 1).ok_or_else(truncated)? as usize;
+        let section = sections.get(offset + 5..offset + 5 + size).ok_or_else(truncated)?;
+        offset += 5 + size;
+        if kind != FCEUX_CPU_SECTION {
+            continue;
+        }
+
+        let mut position = 0;
+        while position + 8 <= section.len() {
+            let name = &section[position..position + 4];
+            let size = read_u32(section, position + 4).unwrap() as usize;
+            let data = section.get(position + 8..position + 8 + size).ok_or_else(truncated)?;
+            if name == FCEUX_RAM_CHUNK {
+                return data.try_into().map_err(|_| {
+                    format!("The RAM of the save state is {} bytes, not {}.", size, RAM_SIZE)
+                });
+            }
+            position += 8 + size;
+        }
+    }
+    Err("The save state holds no RAM.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn reads_the_same_ram_from_dumps_and_save_states() {
+        // dq1.fc0 is stored uncompressed and dq2.fc0 compressed
+        for game in ["dq1", "dq2"] {
+            let dump = read_ram(&fixture(&format!("{}.ram", game))).unwrap();
+            let state = read_ram(&fixture(&format!("{}.fc0", game))).unwrap();
+            assert!(dump == state, "{}", game);
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_save_state() {
+        let state = fs::read(fixture("dq1.fc0")).unwrap();
+        assert!(fceux_ram(&state[..state.len() - 1]).is_err());
+        assert!(fceux_ram(&state[..FCEUX_HEADER_SIZE]).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::rule::{Severity, Violation};
+
+/// Score of a candidate with nothing implausible about it.
+pub(crate) const MAX_SCORE: i32 = 100;
+
+/// Characters that can be followed by `゛` in a name.
+const DAKUTEN_MOJI: &str = "かきくけこさしすせそたちつてとはひふへほ";
+
+/// Characters that can be followed by `゜` in a name.
+const HANDAKUTEN_MOJI: &str = "はひふへほ";
+
+/// Penalises the rules a candidate breaks.
+pub(crate) fn violation_penalty(violations: &[Violation]) -> i32 {
+    violations
+        .iter()
+        .map(|violation| match violation.severity {
+            Severity::Warning => 10,
+            Severity::Error => 50,
+        })
+        .sum()
+}
+
+/// Penalises a name that could not have been entered on the name screen.
+pub(crate) fn name_penalty(name: &[char; 4]) -> i32 {
+    let mut penalty = 0;
+    if name.iter().all(|&c| c == '　') {
+        penalty += 20;
+    }
+    for (index, &c) in name.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|i| name[i]);
+        let valid = match c {
+            '゛' => previous.is_some_and(|p| DAKUTEN_MOJI.contains(p)),
+            '゜' => previous.is_some_and(|p| HANDAKUTEN_MOJI.contains(p)),
+            // Names are padded at the end, so a space is never followed by a character
+            '　' => name[index..].iter().all(|&c| c == '　'),
+            _ => true,
+        };
+        if !valid {
+            penalty += 15;
+        }
+    }
+    penalty
+}
+
+/// Penalises a name that few of the other candidates share.
+///
+/// A single wrong character rarely touches the name, so the name of the intended
+/// password is usually shared by most candidates.
+pub(crate) fn consensus_penalty(name: &[char; 4], names: &[[char; 4]]) -> i32 {
+    if names.is_empty() {
+        return 0;
+    }
+    let shared = names.iter().filter(|&other| other == name).count();
+    (20 * (names.len() - shared) / names.len()) as i32
+}
+
+/// Progress that only grows during a playthrough, used to place a save among others.
+pub(crate) struct Progress {
+    pub experience: u32,
+    // Flags that are rarely cleared once set, such as progress flags and crests
+    pub milestones: Vec<bool>,
+    // Party members, who never leave once they join
+    pub party: Vec<bool>,
+}
+
+/// Where a candidate fits best among reference saves, and what conflicts with them there.
+pub(crate) struct TimelineFit {
+    // Number of references that come before the candidate
+    pub position: usize,
+    pub reference_count: usize,
+    pub penalty: i32,
+    pub conflicts: Vec<&'static str>,
+}
+
+impl TimelineFit {
+    pub(crate) fn describe(&self) -> String {
+        let place = match self.position {
+            0 => "before reference 1".to_string(),
+            position if position == self.reference_count => {
+                format!("after reference {}", position)
+            }
+            position => format!("between references {} and {}", position, position + 1),
+        };
+        if self.conflicts.is_empty() {
+            format!("fits {}", place)
+        } else {
+            format!("fits {}, conflicting in {}", place, self.conflicts.join(", "))
+        }
+    }
+}
+
+/// Finds the place among the references, given in play order, where a candidate fits
+/// with the least penalty for lost experience, cleared milestones or departed members.
+pub(crate) fn timeline_fit(candidate: &Progress, references: &[Progress]) -> TimelineFit {
+    // Counts the flags set in `earlier` but not in `later`
+    let cleared = |earlier: &[bool], later: &[bool]| {
+        earlier.iter().zip(later).filter(|&(&before, &after)| before && !after).count() as i32
+    };
+
+    (0..=references.len())
+        .map(|position| {
+            let (before, after) = references.split_at(position);
+            let mut fit = TimelineFit {
+                position,
+                reference_count: references.len(),
+                penalty: 0,
+                conflicts: vec![],
+            };
+            let mut conflict = |name: &'static str, penalty: i32| {
+                if penalty > 0 {
+                    fit.penalty += penalty;
+                    if !fit.conflicts.contains(&name) {
+                        fit.conflicts.push(name);
+                    }
+                }
+            };
+            for reference in before {
+                conflict("experience", 30 * (reference.experience > candidate.experience) as i32);
+                conflict("flags", 10 * cleared(&reference.milestones, &candidate.milestones));
+                conflict("party", 30 * cleared(&reference.party, &candidate.party));
+            }
+            for reference in after {
+                conflict("experience", 30 * (candidate.experience > reference.experience) as i32);
+                conflict("flags", 10 * cleared(&candidate.milestones, &reference.milestones));
+                conflict("party", 30 * cleared(&candidate.party, &reference.party));
+            }
+            fit
+        })
+        .min_by_key(|fit| fit.penalty)
+        .unwrap()
+}
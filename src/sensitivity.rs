@@ -0,0 +1,50 @@
+use prettytable::{row, Table};
+
+use crate::diff::Change;
+
+/// Label for substitutions whose bytes can no longer be read as game data.
+const UNREADABLE: &str = "(unreadable)";
+
+/// Lists, for each position of a password, the fields that change when its character
+/// is replaced by any other character of the table, whether or not the checksum stays valid.
+///
+/// Fields are listed in the order they are first seen to change.
+pub(crate) fn sensitivity<T>(
+    input: &str,
+    table: &[char; 64],
+    decode: fn(&str) -> Result<T, String>,
+    diff: fn(&T, &T) -> Vec<Change>,
+) -> Result<Vec<Vec<String>>, String> {
+    let original = decode(input)?;
+    let characters = input.chars().collect::<Vec<_>>();
+
+    let mut report = vec![];
+    for index in 0..characters.len() {
+        let mut fields: Vec<String> = vec![];
+        for &moji in table.iter().filter(|&&moji| moji != characters[index]) {
+            let mut substituted = characters.clone();
+            substituted[index] = moji;
+            let changed = match decode(&substituted.iter().collect::<String>()) {
+                Ok(data) => diff(&original, &data).into_iter().map(|change| change.field).collect(),
+                Err(_) => vec![UNREADABLE.to_string()],
+            };
+            for field in changed {
+                if !fields.contains(&field) {
+                    fields.push(field);
+                }
+            }
+        }
+        report.push(fields);
+    }
+    Ok(report)
+}
+
+/// Formats a sensitivity report as a table, one row per password position.
+pub(crate) fn tabulate_sensitivity(input: &str, report: &[Vec<String>]) -> String {
+    let mut table = Table::new();
+    table.add_row(row!["Position", "Character", "Field Count", "Fields"]);
+    for ((index, character), fields) in input.chars().enumerate().zip(report) {
+        table.add_row(row![index, character, fields.len(), fields.join(", ")]);
+    }
+    table.to_string()
+}
@@ -0,0 +1,97 @@
+use crate::complete::Pin;
+
+/// Numbers of characters left unknown around the phrase, tried in turn: first enough to
+/// satisfy the checksum of either game, then one more for when that finds nothing, such
+/// as when the phrase overlaps fields that are set. Each extra character makes the search
+/// 64 times larger, so the wider search stops at the first passwords it finds.
+pub(crate) const FREE_CHARACTERS: [usize; 2] = [3, 4];
+
+/// Most sets of unknown characters to search.
+const MAX_ATTEMPTS: usize = 32;
+
+/// Passwords found for each one shown, so that the most plausible can be picked.
+pub(crate) const CANDIDATES_PER_RESULT: usize = 4;
+
+/// Returns whether a field set by `--set` covers a field of the layout, such as
+/// `items` covering `items[3]` or `gold` covering `gold (high)`.
+pub(crate) fn covers(field: &str, label: &str) -> bool {
+    label
+        .strip_prefix(field)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('[') || rest.starts_with(" ("))
+}
+
+/// Builds search patterns from a base password with the phrase placed at `position`,
+/// each leaving a different set of `free_characters` of the other characters unknown.
+///
+/// Each character is decrypted against the one before it, so the characters on either
+/// side of the phrase are always left unknown to absorb the change. The rest are chosen
+/// so that as few pinned bits as possible are disturbed, keeping the fields asked for.
+pub(crate) fn patterns(
+    base: &[u8],
+    phrase: &[u8],
+    position: usize,
+    pins: &[Pin],
+    free_characters: usize,
+) -> Result<Vec<Vec<Option<u8>>>, String> {
+    let end = position + phrase.len();
+    if end > base.len() {
+        return Err(format!(
+            "The phrase does not fit in a {}-character password at position {}.",
+            base.len(),
+            position
+        ));
+    }
+    let mut pattern = base.iter().map(|&code| Some(code)).collect::<Vec<_>>();
+    for (index, &code) in phrase.iter().enumerate() {
+        pattern[position + index] = Some(code);
+    }
+
+    let around = [position.checked_sub(1), (end < base.len()).then_some(end)];
+    let around = around.into_iter().flatten().collect::<Vec<_>>();
+    for &index in &around {
+        pattern[index] = None;
+    }
+
+    // Freeing a character disturbs its own decryption and that of the next character
+    let disturbed = |index: usize| {
+        pins[index].bit_count() + pins.get(index + 1).map_or(0, |pin| pin.bit_count())
+    };
+    let mut free = (0..base.len())
+        .filter(|index| !(position..end).contains(index) && !around.contains(index))
+        .collect::<Vec<_>>();
+    free.sort_by_key(|&index| disturbed(index));
+
+    let mut patterns = vec![];
+    let mut chosen = vec![];
+    choose(&free, free_characters - around.len(), &mut chosen, &mut |chosen| {
+        let mut attempt = pattern.clone();
+        for &index in chosen {
+            attempt[index] = None;
+        }
+        patterns.push(attempt);
+        patterns.len() < MAX_ATTEMPTS
+    });
+    Ok(patterns)
+}
+
+/// Calls `visit` with each way of choosing `count` of the indices, in order, until it
+/// returns `false`.
+fn choose(
+    indices: &[usize],
+    count: usize,
+    chosen: &mut Vec<usize>,
+    visit: &mut dyn FnMut(&[usize]) -> bool,
+) -> bool {
+    if chosen.len() == count {
+        return visit(chosen);
+    }
+    for (offset, &index) in indices.iter().enumerate() {
+        chosen.push(index);
+        let more = choose(&indices[offset + 1..], count, chosen, visit);
+        chosen.pop();
+        if !more {
+            return false;
+        }
+    }
+    true
+}